@@ -1,21 +1,55 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
-use std::f32::consts::E;
-use super::functions::*;
+use super::context::AngleMode;
+use super::context::ComplexFormat;
+use super::context::Context;
+use super::context::ImplicitMulPrecedence;
+use super::context::IntegerMode;
+use super::context::Radix;
+use super::context::WORD_SIZES;
+use super::context::RoundingMode;
+use super::context::MAX_PRECISION;
+use super::error::CalcError;
+use super::format::{DisplayMode, NumberFormat, DEFAULT_DECIMALS};
 
+use super::parser::Lexer;
+use super::number::Number;
+use super::complex;
+use super::math;
+use super::Expression;
 use super::ExpressionBuilder;
+use super::FunctorKind;
+use super::UserFunctionDef;
 use super::EXP_UNIT_NAME_OPEN_BRK;
 
+/// key which enters the fraction bar of a fraction, pressed twice it enters a mixed number like "1 5/12"
+pub const FRACTION_SEPARATOR: &str = "/";
+/// key which starts the exponent of a number in scientific notation, e.g. "6.022e23"
+pub const EXPONENT_KEY: &str = "EE";
+/// key which enters the variable x of the keypad, the variable the derivatives are taken with respect to
+pub const VARIABLE_KEY: &str = "x";
+
 pub struct Calculator {
     evaluator: ExpressionBuilder,
     constants_map: HashMap<String, String>,
     operand_token: String,
+    // the temporary input is a constant, so the next digit starts a new operand
+    operand_token_complete: bool,
     last_result: String,
+    // value of the last evaluation, the last result is its displayed form
+    last_value: Option<Number>,
+    // fractions are displayed as mixed numbers like "1 5/12" instead of "17/12"
+    mixed_fractions: bool,
+    complex_format: ComplexFormat,
+    number_format: NumberFormat,
+    // the full value of the number on the display, the display itself may be rounded
     last_immediate: String,
     cached_history: String,
     input_tokens: Vec<String>,
     memory: Option<String>,
     allow_auto_complete: bool,
     need_sync_tokens: bool,
+    context: RefCell<Context>,
 }
 pub enum Feature {
     CE,
@@ -24,6 +58,38 @@ pub enum Feature {
     MR,
     DEL,
     Eval,
+    Negate,
+    AngleMode,
+    ComplexMode,
+    // switch the display of complex results between rectangular and polar form
+    ComplexFormat,
+    // switch the display of the last result between decimal and fraction
+    FractionDecimal,
+    FractionMode,
+    ProgrammerMode,
+    // switch the base of the programmer mode between hexadecimal, decimal, octal and binary
+    Radix,
+    WordSize,
+    Signedness,
+    // switch the display between the auto, fixed, scientific and engineering formats
+    DisplayMode,
+    // the displayed value becomes the value of the variable x
+    StoreVariable,
+    // derivative of the input with respect to x, and its value at the value of x
+    Derivative,
+}
+
+// an operand which has no digit yet, e.g. "-" or "0x"
+fn is_unfinished_operand(token: &str) -> bool {
+    matches!(token.trim_start_matches('-'), "" | "0x" | "0o" | "0b")
+}
+
+// expression of a text in which the variable is a parameter, so it does not need a value
+fn parse_with_variable(input: &str, variable: &str, constants: &HashMap<String, String>) -> Result<Expression, CalcError> {
+    if !Lexer::is_identifier(variable) {
+        return Err(CalcError::InvalidName { name: variable.to_string() });
+    }
+    Expression::parse_function_body(input, &[variable.to_string()], constants)
 }
 
 impl Calculator {
@@ -32,21 +98,49 @@ impl Calculator {
             evaluator: ExpressionBuilder::new(),
             constants_map: HashMap::new(),
             operand_token: String::new(),
+            operand_token_complete: false,
             input_tokens: Vec::new(),
             last_result: "0".to_string(),
+            last_value: None,
+            mixed_fractions: false,
+            complex_format: ComplexFormat::Rectangular,
+            number_format: NumberFormat::default(),
             cached_history: String::new(),
             last_immediate: String::new(),
             memory: None,
             allow_auto_complete: true,
             need_sync_tokens: false,
+            context: RefCell::new(Context::new()),
         }
     }
 
-    fn expression_operand_input(&mut self, c: &char) -> Result<Option<String>, String> {       
+    fn expression_operand_input(&mut self, c: &char) -> Result<Option<String>, CalcError> {       
         if !self.last_result.is_empty() {
             // clear last result if user input first operand of the expression
             self.last_result.clear();
         }
+        // a digit after a constant is multiplied with it
+        if self.operand_token_complete {
+            self.push_temp_input()?;
+        }
+        // the numerator and denominator of a fraction are integers, the denominator does not start with 0
+        if self.operand_token.contains('/') && (*c == '.' || (self.operand_token.ends_with('/') && *c == '0')) {
+            return Ok(Some(self.operand_token.clone()));
+        }
+        // the exponent is an integer
+        if self.operand_token.contains('e') && *c == '.' {
+            return Ok(Some(self.operand_token.clone()));
+        }
+        // the programmer mode takes the digits of its base, an operand starts with the prefix of the base
+        if let Some(mode) = self.integer_mode() {
+            if !c.is_digit(mode.radix.value()) {
+                return Ok(Some(self.operand_token.clone()));
+            }
+            if self.operand_token.is_empty() {
+                self.evaluator.prepare_to_push_operand();
+                self.operand_token.push_str(mode.radix.prefix());
+            }
+        }
         // for auto complete
         if self.operand_token.is_empty() {
             self.evaluator.prepare_to_push_operand();
@@ -55,21 +149,83 @@ impl Calculator {
         Ok(Some(self.operand_token.clone()))
     }
 
-    fn expression_constant_input(&mut self, const_val: &String) -> Result<Option<String>, String> {
-        // just clear the temporary input if user pick another constant
-        self.operand_token.clear();
+    fn fraction_input(&mut self) -> Result<Option<String>, CalcError> {
+        let token = &mut self.operand_token;
+        if token.is_empty() || self.operand_token_complete || token.contains('.') || token.ends_with('/') {
+            return Ok(None);
+        }
+        // "1/5" becomes the whole part and the numerator of the mixed number "1 5/"
+        if token.contains('/') {
+            if token.contains(' ') {
+                return Ok(None);
+            }
+            *token = token.replace('/', " ");
+        }
+        token.push('/');
+        Ok(Some(token.clone()))
+    }
+
+    fn exponent_input(&mut self) -> Result<Option<String>, CalcError> {
+        let token = &self.operand_token;
+        if token.is_empty() || self.operand_token_complete || token.contains(['e', '/']) || self.integer_mode().is_some() {
+            return Ok(None);
+        }
+        self.operand_token.push('e');
+        Ok(Some(self.operand_token.clone()))
+    }
+
+    // drop a fraction or an exponent which is not finished, e.g. "1 5/" is taken as 1 and "1.5e-" as 1.5
+    fn complete_operand_token(&mut self) {
+        if self.operand_token.ends_with("e-") {
+            self.operand_token.pop();
+        }
+        if self.operand_token.ends_with('e') {
+            self.operand_token.pop();
+        }
+        if self.operand_token.ends_with('/') {
+            self.operand_token.pop();
+        }
+        if !self.operand_token.contains('/') {
+            if let Some(i) = self.operand_token.find(' ') {
+                self.operand_token.truncate(i);
+            }
+        }
+    }
+
+    fn expression_variable_input(&mut self) -> Result<Option<String>, CalcError> {
+        self.last_result.clear();
+        // a variable after another operand is multiplied with it
+        self.push_temp_input()?;
+        self.evaluator.prepare_to_push_operand();
+
+        let res = self.put_token(VARIABLE_KEY.to_string());
+        if res.is_ok() {
+            self.input_tokens.push(VARIABLE_KEY.to_string());
+        }
+        res
+    }
+
+    fn expression_constant_input(&mut self, name: &String) -> Result<Option<String>, CalcError> {
         // clear last result we don't need it anymore
         self.last_result.clear();
+        // a constant after another operand is multiplied with it
+        self.push_temp_input()?;
 
         // for auto complete
         self.evaluator.prepare_to_push_operand();
 
-        self.operand_token = const_val.clone();
-        Ok(Some(self.operand_token.clone()))
+        // the constant keeps its name in the expression
+        let res = self.put_token(name.clone());
+        if res.is_ok() {
+            self.input_tokens.push(name.clone());
+        }
+        res
     }
 
-    fn put_functor(&mut self, token: String) -> Result<Option<String>, String> {
-        let res = self.evaluator.push_functor(token, self.allow_auto_complete);
+    fn put_functor(&mut self, token: String) -> Result<Option<String>, CalcError> {
+        let allow_auto_complete = self.allow_auto_complete;
+        let evaluator = &mut self.evaluator;
+        let res = Context::scope_current(&self.context, |_| evaluator.push_functor(token, allow_auto_complete));
         match res {
             Ok(Some(_)) => {
                 self.need_sync_tokens = true;
@@ -79,24 +235,39 @@ impl Calculator {
         res
     }
 
-    fn put_token(&mut self, token: String) -> Result<Option<String>, String> {
-        if ExpressionBuilder::is_decimal(token.as_str()) {
-            self.evaluator.push_operand (token.clone());
+    fn put_token(&mut self, token: String) -> Result<Option<String>, CalcError> {
+        if let Some(value) = self.constants_map.get(&token) {
+            self.evaluator.push_constant(token, value);
+            Ok(Some(value.clone()))
+        }
+        else if ExpressionBuilder::is_number(token.as_str()) {
+            if !self.evaluator.push_operand(token.clone()) {
+                let exp_idx = self.evaluator.next_exp_idx();
+                return Err(CalcError::Syntax { token, exp_idx });
+            }
             Ok(Some(token))            
         }
+        else if token == VARIABLE_KEY {
+            // the variable is shown with its value, it has to be stored first
+            let value = self.context.borrow().variables.get(&token).cloned();
+            let value = value.ok_or(CalcError::UnknownVariable { name: token.clone(), exp_idx: self.evaluator.next_exp_idx() })?;
+            self.evaluator.push_variable(token);
+            Ok(Some(value.to_string()))
+        }
         else {            
             self.put_functor(token)
         }
     }
 
-    fn push_temp_input(&mut self) -> Option<String> {
+    // the operand being typed stays when it can not be put, e.g. a number right after another one
+    fn push_temp_input(&mut self) -> Result<Option<String>, CalcError> {
         let mut put_str : Option<String> = None;
         if !self.last_result.is_empty() {
-            self.operand_token = self.last_result.clone();
-            self.last_result.clear();
+            self.operand_token = self.take_last_result();
         }
+        self.complete_operand_token();
         if !self.operand_token.is_empty() {
-            let _ = self.put_token(self.operand_token.clone());
+            self.put_token(self.operand_token.clone())?;
             put_str.replace(self.operand_token.clone());
 
             if self.need_sync_tokens {
@@ -109,17 +280,36 @@ impl Calculator {
             
             self.operand_token.clear();
         }
-        put_str
+        self.operand_token_complete = false;
+        Ok(put_str)
     }
 
-    fn expression_op_input(&mut self, op_name: &String) -> Result<Option<String>, String> {
-        if !self.last_result.is_empty() && op_name == EXP_UNIT_NAME_OPEN_BRK {
+    fn expression_op_input(&mut self, op_name: &String) -> Result<Option<String>, CalcError> {
+        let last_result = self.last_result.clone();
+        let mut temp_token = None;
+        // a bracket or an argument list does not take the last result, e.g. min after = opens an empty min(
+        let opens_bracket = op_name == EXP_UNIT_NAME_OPEN_BRK
+            || Context::scope_current(&self.context, |_| matches!(ExpressionBuilder::functor_kind(op_name), Some(FunctorKind::Function)));
+        if !self.last_result.is_empty() && opens_bracket {
             self.last_result.clear();
         }
         else {
-            let _ = self.push_temp_input();
+            temp_token = self.push_temp_input()?;
         }
         let res = self.put_functor(op_name.clone());
+        if res.is_err() {
+            // the operator is not taken, the expression before it and the operand being typed stay as they were
+            self.need_sync_tokens = false;
+            if let Some(token) = temp_token {
+                self.input_tokens.pop();
+                if last_result.is_empty() {
+                    self.operand_token = token;
+                }
+            }
+            self.last_result = last_result;
+            self.replay_input_tokens();
+            return res;
+        }
         if self.need_sync_tokens {
             self.input_tokens = ExpressionBuilder::tokenize(self.evaluator.to_exp_string());
             self.need_sync_tokens = false;
@@ -141,30 +331,43 @@ impl Calculator {
         }        
     }
 
-    pub fn perform_exp_input(&mut self, input: String) -> Result<Option<String>, String> {
+    pub fn perform_exp_input(&mut self, input: String) -> Result<Option<String>, CalcError> {
         if input.is_empty() {
-            return Err("Empty input".to_string());
+            return Err(CalcError::EmptyExpression);
         }
         self.cached_history.clear();
 
-        let immediate_result: Result<Option<String>, String>;
+        let immediate_result: Result<Option<String>, CalcError>;
+        // the operand being typed is shown as it is, computed values in the display format
+        let mut typed = true;
 
         loop {
             if input.len() == 1 {
                 let c: char = input.chars().next().unwrap();
-                if c.is_ascii_digit() || c == '.' {
+                let hex_digit = self.integer_mode().is_some() && ('A'..='F').contains(&c);
+                if c.is_ascii_digit() || c == '.' || hex_digit {
                     immediate_result = self.expression_operand_input(&c);
                     break;
                 }
             }
-            let constant = self.constants_map.get(&input);
-            match constant {
-                Some(value) => {
-                    immediate_result = self.expression_constant_input(&value.clone());
-                }
-                None => {
-                    immediate_result = self.expression_op_input(&input);
-                }
+            if input == FRACTION_SEPARATOR {
+                immediate_result = self.fraction_input();
+                break;
+            }
+            if input == EXPONENT_KEY {
+                immediate_result = self.exponent_input();
+                break;
+            }
+            typed = false;
+            if input == VARIABLE_KEY {
+                immediate_result = self.expression_variable_input();
+                break;
+            }
+            if self.constants_map.contains_key(&input) {
+                immediate_result = self.expression_constant_input(&input);
+            }
+            else {
+                immediate_result = self.expression_op_input(&input);
             }
             break;
         }
@@ -176,10 +379,46 @@ impl Calculator {
             _ => {}
         };
         
-        immediate_result
+        if typed {
+            return immediate_result;
+        }
+        immediate_result.map(|v| v.map(|v| self.display_value(v)))
     }
 
-    pub fn perform_feature(&mut self, feature: &Feature) -> Result<Option<String>, String> {
+    pub fn perform_text_input(&mut self, input: String) -> Result<Option<String>, CalcError> {
+        // a definition like "f(x) = x²+1"
+        if let Some((name, params, body)) = Expression::parse_function_header(&input) {
+            self.define_function(name.clone(), params, body)?;
+            self.cached_history = self.context.borrow().functions.user_function(&name).unwrap().to_string();
+            return Ok(None);
+        }
+        // an assignment like "rate = 0.07"
+        if let Some((name, value)) = Expression::parse_assignment(&input) {
+            let constants = &self.constants_map;
+            let e = Context::scope_current(&self.context, |_| Expression::parse_with_constants(&value, constants))?;
+            let v = Context::scope_current(&self.context, |_| e.execute())?;
+            self.set_variable(name.clone(), v.clone())?;
+
+            self.set_last_value(v);
+            self.cached_history = format!("{} = {}", name, e.to_string());
+            return Ok(Some(self.last_result.clone()));
+        }
+
+        let constants = &self.constants_map;
+        let e = Context::scope_current(&self.context, |_| Expression::parse_with_constants(&input, constants))?;
+        let v = Context::scope_current(&self.context, |_| e.execute())?;
+
+        self.set_last_value(v);
+        // the typed expression replaces whatever was entered on the keypad
+        self.evaluator = ExpressionBuilder::new();
+        self.cached_history = e.to_string() + " =";
+        self.operand_token.clear();
+        self.input_tokens.clear();
+
+        Ok(Some(self.last_result.clone()))
+    }
+
+    pub fn perform_feature(&mut self, feature: &Feature) -> Result<Option<String>, CalcError> {
         match feature {
             Feature::CE => self.reset_temp(),
             Feature::C => self.reset(),
@@ -187,46 +426,207 @@ impl Calculator {
             Feature::MR => self.memory_recover(),
             Feature::Eval => self.eval(),
             Feature::DEL => self.delete_input(),
+            Feature::Negate => self.toggle_sign(),
+            Feature::AngleMode => self.next_angle_mode(),
+            Feature::FractionDecimal => self.toggle_fraction_display(),
+            Feature::ComplexMode => {
+                let on = !self.complex_mode();
+                self.set_complex_mode(on);
+                Ok(None)
+            },
+            Feature::ComplexFormat => {
+                let format = match self.complex_format {
+                    ComplexFormat::Rectangular => ComplexFormat::Polar,
+                    ComplexFormat::Polar => ComplexFormat::Rectangular,
+                };
+                self.set_complex_format(format);
+                self.redisplay_last_value()
+            },
+            Feature::FractionMode => {
+                let on = !self.fraction_mode();
+                self.set_fraction_mode(on);
+                Ok(None)
+            },
+            Feature::ProgrammerMode => {
+                let mode = match self.integer_mode() {
+                    Some(_) => None,
+                    None => Some(IntegerMode::default()),
+                };
+                self.set_integer_mode(mode);
+                self.redisplay_last_value()
+            },
+            Feature::Radix => self.change_integer_mode(|mode| {
+                mode.radix = match mode.radix {
+                    Radix::Hexadecimal => Radix::Decimal,
+                    Radix::Decimal => Radix::Octal,
+                    Radix::Octal => Radix::Binary,
+                    Radix::Binary => Radix::Hexadecimal,
+                };
+            }),
+            Feature::WordSize => self.change_integer_mode(|mode| {
+                let i = WORD_SIZES.iter().position(|bits| *bits == mode.bits).unwrap_or(0);
+                mode.bits = WORD_SIZES[(i + 1) % WORD_SIZES.len()];
+            }),
+            Feature::Signedness => self.change_integer_mode(|mode| mode.signed = !mode.signed),
+            Feature::DisplayMode => {
+                let mode = match self.number_format.mode {
+                    DisplayMode::Auto => DisplayMode::Fix(DEFAULT_DECIMALS),
+                    DisplayMode::Fix(n) => DisplayMode::Sci(n),
+                    DisplayMode::Sci(_) => DisplayMode::Eng,
+                    DisplayMode::Eng => DisplayMode::Auto,
+                };
+                self.number_format.mode = mode;
+                self.redisplay_last_value()
+            },
+            Feature::StoreVariable => self.store_variable(),
+            Feature::Derivative => self.eval_with(|e| {
+                let d = e.derivative(VARIABLE_KEY)?;
+                Ok((format!("d/dx({}) = {}", e.to_string(), d.to_string()), d.execute()?))
+            }),
+        }
+    }
+
+    // change a setting of the programmer mode and show the last result with it
+    fn change_integer_mode<F>(&mut self, change: F) -> Result<Option<String>, CalcError>
+    where F: FnOnce(&mut IntegerMode) {
+        let mut mode = match self.integer_mode() {
+            Some(mode) => mode,
+            None => return Ok(None),
+        };
+        change(&mut mode);
+        self.set_integer_mode(Some(mode));
+        // an operand being entered is written in the new base
+        let operand = self.operand_token.parse::<Number>().ok().and_then(|v| v.wrap(mode.bits, mode.signed));
+        if let Some(v) = operand {
+            self.operand_token = self.format_value(&v);
+            return Ok(Some(self.operand_token.clone()));
+        }
+        self.redisplay_last_value()
+    }
+
+    // display the last result again after a display setting changed
+    fn redisplay_last_value(&mut self) -> Result<Option<String>, CalcError> {
+        match self.last_value.clone() {
+            Some(v) if !self.last_result.is_empty() => {
+                self.set_last_value(v);
+                Ok(Some(self.last_result.clone()))
+            },
+            _ => Ok(None),
+        }
+    }
+
+    fn set_last_value(&mut self, v: Number) {
+        self.last_result = self.format_value(&v);
+        // the displayed form may not be readable as a number, e.g. "5∠53.13"
+        self.last_immediate = v.to_string();
+        self.last_value = Some(v);
+    }
+
+    // a value given as a string, e.g. an immediate result, in the display format
+    fn display_value(&self, s: String) -> String {
+        match s.parse::<Number>() {
+            Ok(v) => self.format_value(&v),
+            Err(_) => s,
+        }
+    }
+
+    fn format_value(&self, v: &Number) -> String {
+        // integers of the programmer mode are shown in its base, negative ones in two's complement
+        if let Some(mode) = self.integer_mode() {
+            if let Some(digits) = v.to_radix_string(mode.radix.value(), mode.bits) {
+                return format!("{}{}", mode.radix.prefix(), digits);
+            }
+        }
+        if v.is_complex() && self.complex_format == ComplexFormat::Polar {
+            return Context::scope_current(&self.context, |c| {
+                let (precision, rounding, angle_mode) = {
+                    let c = c.borrow();
+                    (c.precision, c.rounding_mode, c.angle_mode)
+                };
+                let digits = Number::working_digits();
+                let r = complex::abs(v, digits);
+                let theta = math::atan2(&v.im(), &v.re(), angle_mode, digits);
+                let format = &self.number_format;
+                format!("{}∠{}", format.format(&r, precision, rounding), format.format(&theta, precision, rounding))
+            });
+        }
+        if v.is_fraction() && self.mixed_fractions {
+            return v.to_mixed_string();
         }
+        self.number_format.format(v, self.precision(), self.rounding_mode())
+    }
+
+    // the last result as the first operand of the next expression
+    fn take_last_result(&mut self) -> String {
+        let operand = match &self.last_value {
+            // integers of the programmer mode are taken as they are displayed
+            Some(v) if self.integer_mode().is_some() => self.format_value(v),
+            Some(v) => v.to_string(),
+            None => self.last_result.clone(),
+        };
+        self.last_result.clear();
+        operand
     }
 
-    fn eval_error(&mut self, temp_token_updated: bool, err: String) -> Result<Option<String>, String> {
-         // reset the evaluator due to it may damaged by evaluation
-         self.evaluator = ExpressionBuilder::new();
+    // show the last result as a decimal if it is shown as a fraction, otherwise as a fraction,
+    // only the display changes, the value keeps all of its digits
+    fn toggle_fraction_display(&mut self) -> Result<Option<String>, CalcError> {
+        let v = match &self.last_value {
+            Some(v) if !self.last_result.is_empty() => v,
+            _ => return Ok(None),
+        };
+        let precision = self.precision();
+        let shown = if self.last_result.contains('/') {
+            v.to_decimal(precision).round_to(precision, self.rounding_mode())
+        }
+        else {
+            // the last digit of a decimal result is rounded, it does not have to match
+            v.to_approximate_fraction(precision.saturating_sub(1).max(1))
+        };
+        self.last_result = self.format_value(&shown);
+        Ok(Some(self.last_result.clone()))
+    }
 
-         // recover evaluator to state before evaluation
+    fn eval_error(&mut self, temp_token_updated: bool, err: CalcError) -> Result<Option<String>, CalcError> {
+         // recover evaluator to state before evaluation, it may be damaged by evaluation
          if temp_token_updated {
              self.operand_token = self.input_tokens.pop().unwrap();
          }
+         self.replay_input_tokens();
 
-         let x = self.input_tokens.clone();
-         x.iter().for_each(|t| {
-             let _ = self.put_token(t.clone());
-         });         
-         // return none like nothing happened
-         Ok(None)
+         // the input stays as it is, so the user can correct it
+         Err(err)
     }
 
-    fn eval(&mut self) -> Result<Option<String>, String> {
+    fn eval(&mut self) -> Result<Option<String>, CalcError> {
+        self.eval_with(|e| Ok((e.to_string() + " =", e.execute()?)))
+    }
+
+    // finish the input and compute its history and value from the expression
+    fn eval_with<F>(&mut self, compute: F) -> Result<Option<String>, CalcError>
+    where F: FnOnce(&Expression) -> Result<(String, Number), CalcError> {
         let mut temp_token_updated = false;
+        self.complete_operand_token();
         if !self.operand_token.is_empty() {
-            let _ = self.evaluator.push_operand(self.operand_token.clone());
+            if !self.evaluator.push_operand(self.operand_token.clone()) {
+                return Err(CalcError::Syntax { token: self.operand_token.clone(), exp_idx: self.evaluator.next_exp_idx() });
+            }
             self.input_tokens.push(self.operand_token.clone());
             self.operand_token.clear();
             temp_token_updated = true;
         }
-        let res = self.evaluator.finish();
+        let evaluator = &mut self.evaluator;
+        let res = Context::scope_current(&self.context, |_| evaluator.finish());
         match res {
             Ok(e) => {
                 // store the final result so that it can be used as the begin of next expression
-                let vr = e.execute();
+                let vr = Context::scope_current(&self.context, |_| compute(&e));
                 match vr {
-                    Ok(v) => {
-                        self.last_result = v.to_string();
-                        self.last_immediate = self.last_result.clone();
+                    Ok((history, v)) => {
+                        self.set_last_value(v);
                         // reset the evaluator after evaluation
                         self.evaluator = ExpressionBuilder::new();
-                        self.cached_history = e.to_string() + " =";
+                        self.cached_history = history;
                         self.operand_token.clear();
                         self.input_tokens.clear();
 
@@ -244,14 +644,14 @@ impl Calculator {
         }
     }
 
-    fn recaculate_after_delete(&mut self) -> Result<Option<String>, String> {
-        // reset the evaluator due to its state is one step forward
-        let mut allow_auto_complete_bck = self.allow_auto_complete;        
+    // rebuild the evaluator from the input tokens, returns the result of each token
+    fn replay_input_tokens(&mut self) -> Vec<Result<Option<String>, CalcError>> {
+        let allow_auto_complete_bck = self.allow_auto_complete;        
         self.evaluator = ExpressionBuilder::new();
 
-        // recover evaluator to current state of inputs
         let mut results = Vec::new();
 
+        // brackets completed before are already in the input tokens
         self.allow_auto_complete = false;
         let x = self.input_tokens.clone();
         for token in x  {
@@ -259,6 +659,13 @@ impl Calculator {
             results.push(last_res);
         }
         self.allow_auto_complete = allow_auto_complete_bck;
+        results
+    }
+
+    fn recaculate_after_delete(&mut self) -> Result<Option<String>, CalcError> {
+        // reset the evaluator due to its state is one step forward
+        // recover evaluator to current state of inputs
+        let results = self.replay_input_tokens();
 
         if self.operand_token.is_empty() {
             let mut last_val = String::new();
@@ -274,7 +681,7 @@ impl Calculator {
             
             match i_opt {
                 Some(_) => {
-                    Ok(Some(last_val))
+                    Ok(Some(self.display_value(last_val)))
                 },
                 None => Ok(Some("0".to_string()))
             }
@@ -285,12 +692,19 @@ impl Calculator {
         }
     }
 
-    fn delete_input(&mut self) -> Result<Option<String>, String> {
+    fn delete_input(&mut self) -> Result<Option<String>, CalcError> {
+        self.operand_token_complete = false;
 
         // try to delete one last char in temporary input...
         match self.operand_token.pop() {
             Some(_) => {
                 // ...if it's possible then return the new temporary input
+                if self.operand_token.ends_with(' ') {
+                    self.operand_token.pop();
+                }
+                if is_unfinished_operand(&self.operand_token) {
+                    self.operand_token.clear();
+                }
                 if self.operand_token.is_empty() {
                     self.recaculate_after_delete()
                 }
@@ -305,9 +719,12 @@ impl Calculator {
                 }
                 // take the last token from input tokens
                 let token = self.input_tokens.pop().unwrap();
-                if ExpressionBuilder::is_decimal(&token) {
+                if ExpressionBuilder::is_number(&token) {
                     self.operand_token = token;
                     self.operand_token.pop();
+                    if is_unfinished_operand(&self.operand_token) {
+                        self.operand_token.clear();
+                    }
                 }
                 
                 self.recaculate_after_delete()
@@ -315,10 +732,56 @@ impl Calculator {
         }
     }
 
-    pub fn reset(&mut self) -> Result<Option<String>, String> {
+    fn toggle_sign(&mut self) -> Result<Option<String>, CalcError> {
+        // the last result becomes the operand of the next expression
+        if self.operand_token.is_empty() && !self.last_result.is_empty() {
+            self.operand_token = self.take_last_result();
+        }
+        // a constant just entered is negated as its value
+        let last_constant = self.input_tokens.last().and_then(|t| self.constants_map.get(t)).cloned();
+        if let (true, Some(value)) = (self.operand_token.is_empty(), last_constant) {
+            self.input_tokens.pop();
+            self.replay_input_tokens();
+            self.evaluator.prepare_to_push_operand();
+            self.operand_token = value;
+            self.operand_token_complete = true;
+        }
+        if self.operand_token.is_empty() {
+            return Ok(None);
+        }
+
+        // while an exponent is entered, the exponent changes its sign
+        if let Some(i) = self.operand_token.find('e') {
+            let exponent = self.operand_token.split_off(i + 1);
+            match exponent.strip_prefix('-') {
+                Some(digits) => self.operand_token.push_str(digits),
+                None => self.operand_token.push_str(&format!("-{}", exponent)),
+            }
+            self.last_immediate = self.operand_token.clone();
+            return Ok(Some(self.operand_token.clone()));
+        }
+
+        // both parts of a complex number change their sign
+        let complex = self.operand_token.parse::<Number>().ok().filter(|v| v.is_complex());
+        if let Some(v) = complex {
+            self.operand_token = (-v).to_string();
+        }
+        else if self.operand_token.starts_with('-') {
+            self.operand_token.remove(0);
+        }
+        else if self.operand_token != "0" {
+            self.operand_token.insert(0, '-');
+        }
+        self.last_immediate = self.operand_token.clone();
+        Ok(Some(self.operand_token.clone()))
+    }
+
+    pub fn reset(&mut self) -> Result<Option<String>, CalcError> {
         self.last_result = "0".to_string();
+        self.last_value = None;
         self.last_immediate = "0".to_string();
         self.operand_token.clear();
+        self.operand_token_complete = false;
         self.input_tokens.clear();
         self.evaluator = ExpressionBuilder::new();
         self.cached_history.clear();
@@ -327,35 +790,364 @@ impl Calculator {
         Ok(Some(self.last_result.clone()))
     }
 
-    fn reset_temp(&mut self) -> Result<Option<String>, String> {
+    fn reset_temp(&mut self) -> Result<Option<String>, CalcError> {
         self.operand_token.clear();
+        self.operand_token_complete = false;
         self.last_result.clear();
 
-        self.evaluator.just_return_imediate_result()
+        let evaluator = &self.evaluator;
+        let res = Context::scope_current(&self.context, |_| evaluator.just_return_imediate_result());
+        res.map(|v| v.map(|v| self.display_value(v)))
+    }
+
+    // store the displayed value in the variable of the keypad, which ends the input like an assignment
+    fn store_variable(&mut self) -> Result<Option<String>, CalcError> {
+        let v = match self.last_immediate.parse::<Number>() {
+            Ok(v) => v,
+            Err(_) => return Ok(None),
+        };
+        self.set_variable(VARIABLE_KEY.to_string(), v.clone())?;
+
+        self.set_last_value(v);
+        self.evaluator = ExpressionBuilder::new();
+        self.cached_history = format!("{} = {}", VARIABLE_KEY, self.last_result);
+        self.operand_token.clear();
+        self.operand_token_complete = false;
+        self.input_tokens.clear();
+        Ok(Some(self.last_result.clone()))
     }
 
-    fn memory_store(&mut self) -> Result<Option<String>, String> {
+    fn memory_store(&mut self) -> Result<Option<String>, CalcError> {
         if self.last_immediate.is_empty() {
             return Ok(None);
         }
 
-        if ExpressionBuilder::is_decimal(self.last_immediate.as_str()) {
+        if ExpressionBuilder::is_number(self.last_immediate.as_str()) {
             self.memory.replace(self.last_immediate.clone());
         }
         Ok(None)
     }
 
-    fn memory_recover(&mut self) -> Result<Option<String>, String> {
+    fn memory_recover(&mut self) -> Result<Option<String>, CalcError> {
         match self.memory.clone() {
             Some(v) => {
                 self.operand_token = v.clone();
+                self.operand_token_complete = false;
                 Ok(Some(v))
             },
             None => Ok(None)
         }
     }
 
+    fn next_angle_mode(&mut self) -> Result<Option<String>, CalcError> {
+        let mode = match self.angle_mode() {
+            AngleMode::Degree => AngleMode::Radian,
+            AngleMode::Radian => AngleMode::Gradian,
+            AngleMode::Gradian => AngleMode::Degree,
+        };
+        self.set_angle_mode(mode);
+        Ok(None)
+    }
+
+    pub fn angle_mode(&self) -> AngleMode {
+        self.context.borrow().angle_mode
+    }
+
+    pub fn set_angle_mode(&mut self, mode: AngleMode) {
+        self.context.borrow_mut().angle_mode = mode;
+    }
+
+    /// define or redefine a user function, the body may call the function itself
+    pub fn define_function(&mut self, name: String, params: Vec<String>, body: String) -> Result<(), CalcError> {
+        if !Lexer::is_identifier(&name) {
+            return Err(CalcError::InvalidName { name: name.clone() });
+        }
+        for (i, param) in params.iter().enumerate() {
+            if !Lexer::is_identifier(param) {
+                return Err(CalcError::InvalidName { name: param.clone() });
+            }
+            if params[..i].contains(param) {
+                return Err(CalcError::NameInUse { name: param.clone() });
+            }
+        }
+
+        let constants = &self.constants_map;
+        Context::scope_current(&self.context, |c| {
+            if c.borrow().functions.is_built_in(&name) || constants.contains_key(&name) {
+                return Err(CalcError::NameInUse { name: name.clone() });
+            }
+            if c.borrow().variables.contains_key(&name) {
+                return Err(CalcError::NameInUse { name: name.clone() });
+            }
+
+            // register the name first, so the body can call the function recursively
+            let declaration = UserFunctionDef { name: name.clone(), params: params.clone(), body: Expression { root: None } };
+            let previous = c.borrow_mut().functions.define(declaration);
+
+            match Expression::parse_function_body(&body, &params, constants) {
+                Ok(body) => {
+                    c.borrow_mut().functions.define(UserFunctionDef { name, params, body });
+                    Ok(())
+                },
+                Err(err) => {
+                    c.borrow_mut().functions.restore(&name, previous);
+                    Err(err)
+                }
+            }
+        })
+    }
+
+    /// simplified form of a text expression, e.g. "2x+3x−x×1" is "4x"
+    pub fn simplify(&mut self, input: String) -> Result<String, CalcError> {
+        let constants = &self.constants_map;
+        Context::scope_current(&self.context, |_| {
+            let e = Expression::parse_with_constants(&input, constants)?;
+            Ok(e.simplify()?.to_string())
+        })
+    }
+
+    /// derivative of a text expression with respect to a variable, e.g. "x²+3x" and "x" give "2x+3"
+    pub fn derivative(&mut self, input: String, variable: &str) -> Result<String, CalcError> {
+        let constants = &self.constants_map;
+        Context::scope_current(&self.context, |_| {
+            let e = parse_with_variable(&input, variable, constants)?;
+            Ok(e.derivative(variable)?.to_string())
+        })
+    }
+
+    /// value of the derivative of a text expression at a point, e.g. "x²+3x" at x = 2 gives 7
+    pub fn derivative_at(&mut self, input: String, variable: &str, point: Number) -> Result<Number, CalcError> {
+        let constants = &self.constants_map;
+        Context::scope_current(&self.context, |c| {
+            let d = parse_with_variable(&input, variable, constants)?.derivative(variable)?;
+            // the variable is the parameter of a call with the point as its argument
+            c.borrow_mut().call_frames.push(vec![point]);
+            let v = d.execute();
+            c.borrow_mut().call_frames.pop();
+            v
+        })
+    }
+
+    /// definite integral of a text expression in a variable from a to b, with an estimate of its error,
+    /// e.g. "x²" in x from 0 to 3 gives 9
+    pub fn integrate(&mut self, input: String, variable: &str, a: Number, b: Number) -> Result<(Number, Number), CalcError> {
+        let constants = &self.constants_map;
+        Context::scope_current(&self.context, |c| {
+            if a.is_complex() || b.is_complex() || !a.is_finite() || !b.is_finite() {
+                return Err(CalcError::Domain { exp_idx: -1 });
+            }
+            let e = parse_with_variable(&input, variable, constants)?;
+            let f = |x: &Number| {
+                c.borrow_mut().call_frames.push(vec![x.clone()]);
+                let v = e.execute();
+                c.borrow_mut().call_frames.pop();
+                match v? {
+                    v if v.is_complex() || !v.is_finite() => Err(CalcError::Domain { exp_idx: -1 }),
+                    v => Ok(v),
+                }
+            };
+            let tolerance = c.borrow().integration_tolerance();
+            math::integrate(f, &a, &b, &tolerance, Number::working_digits())?.ok_or(CalcError::ToleranceNotReached { exp_idx: -1 })
+        })
+    }
+
+    pub fn integration_tolerance(&self) -> Number {
+        self.context.borrow().integration_tolerance()
+    }
+
+    /// error allowed in the integrals relative to the integral of the absolute value, e.g. 1e-20,
+    /// None for a tolerance which follows the precision
+    pub fn set_integration_tolerance(&mut self, tolerance: Option<Number>) {
+        self.context.borrow_mut().integration_tolerance = tolerance.map(|v| v.abs());
+    }
+
+    pub fn delete_function(&mut self, name: &str) -> bool {
+        self.context.borrow_mut().functions.remove(name)
+    }
+
+    /// definitions of the user functions, e.g. "f(x) = x²+1"
+    pub fn user_functions(&self) -> Vec<String> {
+        self.context.borrow().functions.user_functions().iter().map(|def| def.to_string()).collect()
+    }
+
+    /// assign a variable, expressions using it see the new value the next time they are executed
+    pub fn set_variable(&mut self, name: String, value: Number) -> Result<(), CalcError> {
+        if !Lexer::is_identifier(&name) {
+            return Err(CalcError::InvalidName { name: name.clone() });
+        }
+        if self.constants_map.contains_key(&name) || self.context.borrow().functions.contains(&name) {
+            return Err(CalcError::NameInUse { name: name.clone() });
+        }
+        self.context.borrow_mut().variables.insert(name, value);
+        Ok(())
+    }
+
+    pub fn remove_variable(&mut self, name: &str) -> bool {
+        self.context.borrow_mut().variables.remove(name).is_some()
+    }
+
+    /// current bindings sorted by name
+    pub fn variables(&self) -> Vec<(String, Number)> {
+        let mut variables: Vec<(String, Number)> = self.context.borrow().variables.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        variables.sort_by(|a, b| a.0.cmp(&b.0));
+        variables
+    }
+
+    pub fn implicit_mul_precedence(&self) -> ImplicitMulPrecedence {
+        self.context.borrow().implicit_mul_precedence
+    }
+
+    /// choose whether 1/2π means 1/(2π) or (1/2)π
+    pub fn set_implicit_mul_precedence(&mut self, precedence: ImplicitMulPrecedence) {
+        self.context.borrow_mut().implicit_mul_precedence = precedence;
+    }
+
+    pub fn set_max_recursion_depth(&mut self, depth: usize) {
+        self.context.borrow_mut().max_recursion_depth = depth;
+    }
+
+    pub fn precision(&self) -> u64 {
+        self.context.borrow().precision
+    }
+
+    /// significant digits of the results, from 1 to MAX_PRECISION
+    pub fn set_precision(&mut self, digits: u64) {
+        self.context.borrow_mut().precision = digits.clamp(1, MAX_PRECISION);
+    }
+
+    pub fn rounding_mode(&self) -> RoundingMode {
+        self.context.borrow().rounding_mode
+    }
+
+    pub fn set_rounding_mode(&mut self, mode: RoundingMode) {
+        self.context.borrow_mut().rounding_mode = mode;
+    }
+
+    pub fn fraction_mode(&self) -> bool {
+        self.context.borrow().fraction_mode
+    }
+
+    /// compute with exact fractions, e.g. 1÷3×3 is exactly 1
+    pub fn set_fraction_mode(&mut self, on: bool) {
+        self.context.borrow_mut().fraction_mode = on;
+    }
+
+    pub fn mixed_fractions(&self) -> bool {
+        self.mixed_fractions
+    }
+
+    /// display fractions as mixed numbers like "1 5/12"
+    pub fn set_mixed_fractions(&mut self, on: bool) {
+        self.mixed_fractions = on;
+    }
+
+    pub fn complex_mode(&self) -> bool {
+        self.context.borrow().complex_mode
+    }
+
+    /// allow complex results, e.g. √(−4) = 2i
+    pub fn set_complex_mode(&mut self, on: bool) {
+        self.context.borrow_mut().complex_mode = on;
+    }
+
+    pub fn complex_format(&self) -> ComplexFormat {
+        self.complex_format
+    }
+
+    /// display complex results as a+bi or r∠θ
+    pub fn set_complex_format(&mut self, format: ComplexFormat) {
+        self.complex_format = format;
+    }
+
+    pub fn number_format(&self) -> NumberFormat {
+        self.number_format
+    }
+
+    /// choose how results are written, e.g. with a fixed number of decimals or grouped thousands
+    pub fn set_number_format(&mut self, format: NumberFormat) {
+        self.number_format = format;
+    }
+
+    pub fn ieee_passthrough(&self) -> bool {
+        self.context.borrow().ieee_passthrough
+    }
+
+    /// give ∞, -∞ and NaN as results instead of overflow and invalid input errors, e.g. 1÷0 = ∞
+    pub fn set_ieee_passthrough(&mut self, on: bool) {
+        self.context.borrow_mut().ieee_passthrough = on;
+    }
+
+    pub fn integer_mode(&self) -> Option<IntegerMode> {
+        self.context.borrow().integer_mode
+    }
+
+    /// turn the programmer mode on with the given word and base, or off with None
+    pub fn set_integer_mode(&mut self, mode: Option<IntegerMode>) {
+        self.context.borrow_mut().integer_mode = mode;
+        // the last result becomes an integer of the new word
+        if let (Some(mode), Some(v)) = (mode, &self.last_value) {
+            self.last_value = v.re().trunc().wrap(mode.bits, mode.signed);
+        }
+    }
+
     pub fn add_constant(&mut self, name: String, value: String) {
         self.constants_map.insert(name, value);
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // keys pressed one after another, with the result of the last one
+    fn press(calculator: &mut Calculator, keys: &[&str]) -> Result<Option<String>, CalcError> {
+        let mut res = Ok(None);
+        for key in keys {
+            res = match *key {
+                "=" => calculator.perform_feature(&Feature::Eval),
+                _ => calculator.perform_exp_input(key.to_string()),
+            };
+        }
+        res
+    }
+
+    #[test]
+    fn function_after_an_invalid_operand_is_an_error() {
+        let mut calculator = Calculator::new();
+        calculator.add_constant("i".to_string(), "i".to_string());
+        assert!(press(&mut calculator, &["i", "+", "sin"]).is_err());
+    }
+
+    #[test]
+    fn argument_list_without_an_operand_starts_empty() {
+        let mut calculator = Calculator::new();
+        assert_eq!(press(&mut calculator, &["min", "1", ",", "2", ",", "3", "="]).unwrap(), Some("1".to_string()));
+        assert_eq!(press(&mut calculator, &["max", "1", ",", "5", ",", "3", "="]).unwrap(), Some("5".to_string()));
+        assert_eq!(press(&mut calculator, &["logb", "8", ",", "2", "="]).unwrap(), Some("3".to_string()));
+        // an operand typed before the function is its first argument
+        assert_eq!(press(&mut calculator, &["8", "logb", "2", "="]).unwrap(), Some("3".to_string()));
+    }
+
+    #[test]
+    fn fraction_display_keeps_the_value() {
+        let mut calculator = Calculator::new();
+        let root = press(&mut calculator, &["2", "√", "="]).unwrap();
+        calculator.perform_feature(&Feature::FractionDecimal).unwrap();
+        assert_eq!(calculator.perform_feature(&Feature::FractionDecimal).unwrap(), root);
+        calculator.perform_feature(&Feature::FractionDecimal).unwrap();
+        assert_eq!(press(&mut calculator, &["×", "1", "="]).unwrap(), root);
+    }
+
+    #[test]
+    fn number_after_a_number_is_an_error() {
+        let mut calculator = Calculator::new();
+        // deleting the operator leaves 3 in the expression
+        press(&mut calculator, &["3", "+"]).unwrap();
+        calculator.perform_feature(&Feature::DEL).unwrap();
+        assert!(press(&mut calculator, &["2", "="]).is_err());
+
+        let mut calculator = Calculator::new();
+        calculator.add_constant("π".to_string(), "3.14".to_string());
+        assert_eq!(press(&mut calculator, &["2", "π", "="]).unwrap(), Some("6.28".to_string()));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,202 @@
+use super::context::AngleMode;
+use super::math;
+use super::number::{Number, MAX_EXPONENT};
+
+// extra digits carried by the intermediate steps of a function
+const GUARD_DIGITS: u64 = 10;
+
+fn two() -> Number {
+    Number::from(2)
+}
+
+/// modulus |z|
+pub fn abs(z: &Number, digits: u64) -> Number {
+    let (x, y) = (z.re(), z.im());
+    (&x * &x + &y * &y).sqrt(digits)
+}
+
+/// argument of z in radians, in (−π, π]
+pub fn arg(z: &Number, digits: u64) -> Number {
+    math::atan2(&z.im(), &z.re(), AngleMode::Radian, digits)
+}
+
+/// complex number of the given modulus and argument in radians
+pub fn from_polar(r: &Number, theta: &Number, digits: u64) -> Number {
+    Number::complex(r * theta.cos(digits), r * theta.sin(digits))
+}
+
+/// principal square root, e.g. √(−4) = 2i
+pub fn sqrt(z: &Number, digits: u64) -> Number {
+    let (x, y) = (z.re(), z.im());
+    if y.is_zero() {
+        if x.is_negative() {
+            return Number::complex(Number::zero(), (-x).sqrt(digits));
+        }
+        return x.sqrt(digits);
+    }
+    // t = √((|z| + |x|)/2), the other part is y/2t, so that nothing cancels
+    let work = digits + GUARD_DIGITS;
+    let t = (abs(z, work) + x.abs()).div(&two(), work).sqrt(work);
+    let other = y.abs().div(&(&t * &two()), work);
+    let (re, im) = if x.is_negative() { (other, t) } else { (t, other) };
+    let im = if y.is_negative() { -im } else { im };
+    Number::complex(re.round_digits(digits), im.round_digits(digits))
+}
+
+/// principal natural logarithm, z must not be 0
+pub fn ln(z: &Number, digits: u64) -> Number {
+    let (x, y) = (z.re(), z.im());
+    if y.is_zero() && x.is_positive() {
+        return x.ln(digits);
+    }
+    // ln z = ln|z| + i·arg z, ln|z| = ln(x² + y²)/2 has no rounded square root
+    let re = (&x * &x + &y * &y).ln(digits + GUARD_DIGITS).div(&two(), digits);
+    Number::complex(re, arg(z, digits))
+}
+
+/// e^z, None when it overflows
+pub fn exp(z: &Number, digits: u64) -> Option<Number> {
+    let (x, y) = (z.re(), z.im());
+    let r = x.exp(digits + GUARD_DIGITS)?;
+    if y.is_zero() {
+        return Some(r.round_digits(digits));
+    }
+    Some(from_polar(&r, &y, digits))
+}
+
+// integer power by repeated squaring
+fn powi(z: &Number, n: i64, digits: u64) -> Number {
+    let work = digits + n.unsigned_abs().to_string().len() as u64 + 2;
+    let mut result = Number::one();
+    let mut base = z.clone();
+    let mut e = n.unsigned_abs();
+    while e > 0 {
+        if e & 1 == 1 {
+            result = (&result * &base).round_digits(work);
+        }
+        e >>= 1;
+        if e > 0 {
+            base = (&base * &base).round_digits(work);
+        }
+    }
+    if n < 0 {
+        return Number::one().div(&result, digits);
+    }
+    result.round_digits(digits)
+}
+
+/// principal value of z^w, z must not be 0. None when it overflows
+pub fn pow(z: &Number, w: &Number, digits: u64) -> Option<Number> {
+    if let Some(n) = w.to_i64() {
+        if n.abs() <= 1_000_000 {
+            if (z.magnitude() + 1).saturating_mul(n) > MAX_EXPONENT + 1 {
+                return None;
+            }
+            return Some(powi(z, n, digits));
+        }
+    }
+    // z^w = e^(w·ln z)
+    let work = digits + GUARD_DIGITS;
+    exp(&(w * ln(z, work)), digits)
+}
+
+/// sin z = sin x·cosh y + i·cos x·sinh y
+pub fn sin(z: &Number, digits: u64) -> Option<Number> {
+    let (x, y) = (z.re(), z.im());
+    let work = digits + GUARD_DIGITS;
+    let re = x.sin(work) * math::cosh(&y, work)?;
+    let im = x.cos(work) * math::sinh(&y, work)?;
+    Some(Number::complex(re.round_digits(digits), im.round_digits(digits)))
+}
+
+/// cos z = cos x·cosh y − i·sin x·sinh y
+pub fn cos(z: &Number, digits: u64) -> Option<Number> {
+    let (x, y) = (z.re(), z.im());
+    let work = digits + GUARD_DIGITS;
+    let re = x.cos(work) * math::cosh(&y, work)?;
+    let im = -(x.sin(work) * math::sinh(&y, work)?);
+    Some(Number::complex(re.round_digits(digits), im.round_digits(digits)))
+}
+
+/// sinh z = sinh x·cos y + i·cosh x·sin y
+pub fn sinh(z: &Number, digits: u64) -> Option<Number> {
+    let (x, y) = (z.re(), z.im());
+    let work = digits + GUARD_DIGITS;
+    let re = math::sinh(&x, work)? * y.cos(work);
+    let im = math::cosh(&x, work)? * y.sin(work);
+    Some(Number::complex(re.round_digits(digits), im.round_digits(digits)))
+}
+
+/// cosh z = cosh x·cos y + i·sinh x·sin y
+pub fn cosh(z: &Number, digits: u64) -> Option<Number> {
+    let (x, y) = (z.re(), z.im());
+    let work = digits + GUARD_DIGITS;
+    let re = math::cosh(&x, work)? * y.cos(work);
+    let im = math::sinh(&x, work)? * y.sin(work);
+    Some(Number::complex(re.round_digits(digits), im.round_digits(digits)))
+}
+
+// on the branch cuts along the real axis, a real argument is taken as the limit from the upper half-plane
+
+/// asin z = −i·ln(iz + √(1 − z²))
+pub fn asin(z: &Number, digits: u64) -> Number {
+    let work = digits + GUARD_DIGITS;
+    if !z.is_complex() && z.abs() > Number::one() {
+        // asin x = ±π/2 + i·acosh|x|
+        let re = Number::pi(work).div(&two(), digits);
+        let re = if z.is_negative() { -re } else { re };
+        return Number::complex(re, math::acosh(&z.abs(), digits));
+    }
+    let i = Number::i();
+    let w = &i * z + sqrt(&(Number::one() - z * z), work);
+    -(i * ln(&w, work)).round_digits(digits)
+}
+
+/// acos z = π/2 − asin z
+pub fn acos(z: &Number, digits: u64) -> Number {
+    let work = digits + GUARD_DIGITS;
+    (Number::pi(work).div(&two(), work) - asin(z, work)).round_digits(digits)
+}
+
+/// atan z = i/2·(ln(1 − iz) − ln(1 + iz)), z must not be ±i
+pub fn atan(z: &Number, digits: u64) -> Number {
+    let work = digits + GUARD_DIGITS;
+    let iz = Number::i() * z;
+    let d = ln(&(Number::one() - &iz), work) - ln(&(Number::one() + &iz), work);
+    (Number::i() * d).div(&two(), digits)
+}
+
+/// asinh z = ln(z + √(z² + 1)), odd, so it is computed for a non-negative real part
+pub fn asinh(z: &Number, digits: u64) -> Number {
+    if z.re().is_negative() {
+        return -asinh(&-z, digits);
+    }
+    let work = digits + GUARD_DIGITS;
+    ln(&(z + sqrt(&(z * z + Number::one()), work)), digits)
+}
+
+/// acosh z = ln(z + √(z + 1)·√(z − 1))
+pub fn acosh(z: &Number, digits: u64) -> Number {
+    let work = digits + GUARD_DIGITS;
+    if !z.is_complex() && *z < Number::one() {
+        // acosh x = i·acos x for |x| ≤ 1, acosh|x| + iπ below −1
+        if *z >= -Number::one() {
+            return Number::complex(Number::zero(), acos(z, digits));
+        }
+        return Number::complex(math::acosh(&z.abs(), digits), Number::pi(digits));
+    }
+    let w = z + sqrt(&(z + Number::one()), work) * sqrt(&(z - Number::one()), work);
+    ln(&w, digits)
+}
+
+/// atanh z = (ln(1 + z) − ln(1 − z))/2, z must not be ±1
+pub fn atanh(z: &Number, digits: u64) -> Number {
+    let work = digits + GUARD_DIGITS;
+    if !z.is_complex() && z.abs() > Number::one() {
+        // atanh x = atanh(1/x) + iπ/2
+        let re = Number::one().div(z, work).atanh(digits);
+        return Number::complex(re, Number::pi(work).div(&two(), digits));
+    }
+    let d = ln(&(Number::one() + z), work) - ln(&(Number::one() - z), work);
+    d.div(&two(), digits)
+}
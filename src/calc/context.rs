@@ -1,18 +1,172 @@
 
 use std::cell::RefCell;
+use std::collections::HashMap;
+
+use super::expression::FunctionRegistry;
+use super::number::Number;
 
 thread_local! {
     // default context for a thread    
     pub static CURRENT_CONTEXT: RefCell<Context> = RefCell::new(Context::new());
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum AngleMode {
+    Degree,
+    Radian,
+    Gradian,
+}
+
+impl std::fmt::Display for AngleMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            AngleMode::Degree => "DEG",
+            AngleMode::Radian => "RAD",
+            AngleMode::Gradian => "GRAD",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// how tightly an implicit multiplication binds, e.g. whether 1/2π is 1/(2π) or (1/2)π
+#[derive(Clone, Copy, PartialEq)]
+pub enum ImplicitMulPrecedence {
+    // before × and ÷, 1/2π = 1/(2π)
+    High,
+    // same as × and ÷, 1/2π = (1/2)π
+    Normal,
+}
+
+/// how results are rounded to the working precision
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RoundingMode {
+    // to the nearest digit, ties to the even digit
+    HalfEven,
+    // to the nearest digit, ties away from 0
+    HalfUp,
+    // to the nearest digit, ties towards 0
+    HalfDown,
+    // away from 0
+    Up,
+    // towards 0
+    Down,
+    // towards +∞
+    Ceiling,
+    // towards -∞
+    Floor,
+}
+
+/// how complex results are displayed
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ComplexFormat {
+    // a+bi
+    Rectangular,
+    // r∠θ, the angle is in the angle mode
+    Polar,
+}
+
+/// base in which the integers of the programmer mode are entered and displayed
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Radix {
+    Binary,
+    Octal,
+    Decimal,
+    Hexadecimal,
+}
+
+impl Radix {
+    pub fn value(&self) -> u32 {
+        match self {
+            Radix::Binary => 2,
+            Radix::Octal => 8,
+            Radix::Decimal => 10,
+            Radix::Hexadecimal => 16,
+        }
+    }
+
+    /// prefix of the literals in this base, e.g. "0x" for 0xFF
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Radix::Binary => "0b",
+            Radix::Octal => "0o",
+            Radix::Decimal => "",
+            Radix::Hexadecimal => "0x",
+        }
+    }
+}
+
+impl std::fmt::Display for Radix {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Radix::Binary => "BIN",
+            Radix::Octal => "OCT",
+            Radix::Decimal => "DEC",
+            Radix::Hexadecimal => "HEX",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// word sizes of the programmer mode
+pub const WORD_SIZES: [u32; 4] = [8, 16, 32, 64];
+
+/// fixed-width integers of the programmer mode
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct IntegerMode {
+    pub bits: u32,
+    pub signed: bool,
+    pub radix: Radix,
+}
+
+impl Default for IntegerMode {
+    fn default() -> Self {
+        Self {
+            bits: 64,
+            signed: true,
+            radix: Radix::Hexadecimal,
+        }
+    }
+}
+
+impl std::fmt::Display for IntegerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // e.g. "HEX i32" or "BIN u8"
+        write!(f, "{} {}{}", self.radix, if self.signed { "i" } else { "u" }, self.bits)
+    }
+}
+
+// significant digits of the results
+pub const DEFAULT_PRECISION: u64 = 32;
+pub const MAX_PRECISION: u64 = 100;
+// digits of the integrals when the tolerance follows the precision, the quadrature rule is given to 36 digits
+const MAX_INTEGRATION_DIGITS: u64 = 30;
+
 pub struct Stack {
-    stack_buffer: Vec<f64>,
+    stack_buffer: Vec<Number>,
 }
 pub struct Context {
     pub execution_stack: Stack,
     pub error_detected: bool,
     pub error_message: String,
+    pub angle_mode: AngleMode,
+    pub functions: FunctionRegistry,
+    pub variables: HashMap<String, Number>,
+    pub implicit_mul_precedence: ImplicitMulPrecedence,
+    // arguments of the user functions being executed, the innermost call is on top
+    pub call_frames: Vec<Vec<Number>>,
+    pub max_recursion_depth: usize,
+    pub precision: u64,
+    pub rounding_mode: RoundingMode,
+    // numbers are exact fractions, only the transcendental functions give decimals
+    pub fraction_mode: bool,
+    // functions give complex results where they have no real ones, e.g. √(−4) = 2i
+    pub complex_mode: bool,
+    // programmer mode, results are truncated to integers and wrap around to the word size
+    pub integer_mode: Option<IntegerMode>,
+    // overflows and invalid operations give ∞, -∞ and NaN as IEEE floating point numbers do, instead of errors
+    pub ieee_passthrough: bool,
+    // error allowed in the integrals relative to the integral of the absolute value, None to follow the precision
+    pub integration_tolerance: Option<Number>,
 }
 
 impl Context {
@@ -21,8 +175,29 @@ impl Context {
             execution_stack: Stack::new(),
             error_detected: false,
             error_message: String::new(),
+            angle_mode: AngleMode::Radian,
+            functions: FunctionRegistry::new(),
+            variables: HashMap::new(),
+            implicit_mul_precedence: ImplicitMulPrecedence::High,
+            call_frames: Vec::new(),
+            max_recursion_depth: 100,
+            precision: DEFAULT_PRECISION,
+            rounding_mode: RoundingMode::HalfEven,
+            fraction_mode: false,
+            complex_mode: false,
+            integer_mode: None,
+            ieee_passthrough: false,
+            integration_tolerance: None,
+        }
+    }
+    /// tolerance of the integrals, by default two thirds of the digits of the precision are accurate
+    pub fn integration_tolerance(&self) -> Number {
+        match &self.integration_tolerance {
+            Some(tolerance) => tolerance.clone(),
+            None => format!("1e-{}", self.precision.min(MAX_INTEGRATION_DIGITS) * 2 / 3).parse().unwrap(),
         }
     }
+
     pub fn scope_current<F, T>(context:& RefCell<Context>, scope: F) -> T
     where F: FnOnce(&RefCell<Context>) -> T {
         CURRENT_CONTEXT.with(|c| {
@@ -36,12 +211,12 @@ impl Context {
         })
     }
 
-    pub fn with_current<F>(f: F)
-    where F: FnOnce(&RefCell<Context>)
+    pub fn with_current<F, T>(f: F) -> T
+    where F: FnOnce(&RefCell<Context>) -> T
     {
         CURRENT_CONTEXT.with(|c| {
-            f(c);
-        });
+            f(c)
+        })
     }
 }
 
@@ -52,15 +227,15 @@ impl Stack {
         }
     }
 
-    pub fn push_val(&mut self, val: f64) {
+    pub fn push_val(&mut self, val: Number) {
         self.stack_buffer.push(val);
     }
 
-    pub fn pop_val(&mut self) -> Option<f64> {
+    pub fn pop_val(&mut self) -> Option<Number> {
         self.stack_buffer.pop()
     }
 
-    pub fn top_val(&self) -> Option<&f64> {
+    pub fn top_val(&self) -> Option<&Number> {
         self.stack_buffer.last()
     }
 
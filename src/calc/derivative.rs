@@ -0,0 +1,175 @@
+use super::context::Context;
+use super::error::CalcError;
+use super::expression::*;
+use super::math;
+use super::number::Number;
+use super::simplify::{simplify, Term};
+
+impl Expression {
+    /// derivative with respect to a variable or a parameter, simplified,
+    /// e.g. the derivative of x²+3x with respect to x is 2x+3
+    pub fn derivative(&self, variable: &str) -> Result<Expression, CalcError> {
+        let term = self.to_term().ok_or(CalcError::EmptyExpression)?;
+        Expression::from_term(&simplify(derive(&term, variable, &mut Vec::new())?))
+    }
+}
+
+/// derivative of a term by the sum, product, quotient and chain rules,
+/// calls are the user functions being differentiated through
+fn derive(term: &Term, x: &str, calls: &mut Vec<String>) -> Result<Term, CalcError> {
+    let args = match term {
+        Term::Constant { .. } => return Ok(number(0)),
+        Term::Variable(name) | Term::Parameter(name, _) => return Ok(number(if name == x { 1 } else { 0 })),
+        Term::Apply(_, args) => args,
+    };
+    if !depends_on(term, x) {
+        return Ok(number(0));
+    }
+
+    let name = match term {
+        Term::Apply(name, _) => name.as_str(),
+        _ => unreachable!(),
+    };
+    let a = args[0].clone();
+    let da = derive(&a, x, calls)?;
+    // the trigonometric functions take and give angles in the angle mode
+    let radians = || {
+        let mode = Context::with_current(|c| c.borrow().angle_mode);
+        Term::number(math::to_radians(&Number::one(), mode, Number::working_digits()))
+    };
+
+    let d = match name {
+        EXP_UNIT_NAME_ADD => add(da, derive(&args[1], x, calls)?),
+        EXP_UNIT_NAME_SUB => sub(da, derive(&args[1], x, calls)?),
+        EXP_UNIT_NAME_NEG => neg(da),
+        // (ab)' = a'b + ab'
+        EXP_UNIT_NAME_MUL => {
+            let b = args[1].clone();
+            let db = derive(&b, x, calls)?;
+            add(mul(da, b), mul(a, db))
+        },
+        // (a/b)' = a'/b − ab'/b²
+        EXP_UNIT_NAME_DIV => {
+            let b = args[1].clone();
+            let db = derive(&b, x, calls)?;
+            sub(div(da, b.clone()), div(mul(a, db), apply(EXP_UNIT_NAME_SQR, b)))
+        },
+        EXP_UNIT_NAME_SQR => mul(mul(number(2), a), da),
+        EXP_UNIT_NAME_SQRT => div(da, mul(number(2), apply(EXP_UNIT_NAME_SQRT, a))),
+        EXP_UNIT_NAME_INV => neg(div(da, apply(EXP_UNIT_NAME_SQR, a))),
+        EXP_UNIT_NAME_POW => {
+            let b = args[1].clone();
+            if depends_on(&b, x) {
+                // (a^b)' = a^b(b'ln a + ba'/a)
+                let db = derive(&b, x, calls)?;
+                let rate = add(mul(db, apply(EXP_UNIT_NAME_LN, a.clone())), div(mul(b, da), a));
+                mul(term.clone(), rate)
+            }
+            else {
+                let exponent = sub(b.clone(), number(1));
+                mul(mul(b, Term::apply(EXP_UNIT_NAME_POW, vec![a, exponent])), da)
+            }
+        },
+        EXP_UNIT_NAME_EXP => mul(term.clone(), da),
+        EXP_UNIT_NAME_EXP10 => mul(mul(term.clone(), apply(EXP_UNIT_NAME_LN, number(10))), da),
+        EXP_UNIT_NAME_LN => div(da, a),
+        EXP_UNIT_NAME_LOG10 => div(da, mul(a, apply(EXP_UNIT_NAME_LN, number(10)))),
+        EXP_UNIT_NAME_LOG2 => div(da, mul(a, apply(EXP_UNIT_NAME_LN, number(2)))),
+        EXP_UNIT_NAME_ABS => div(mul(a, da), term.clone()),
+        EXP_UNIT_NAME_SIN => mul(mul(apply(EXP_UNIT_NAME_COS, a), radians()), da),
+        EXP_UNIT_NAME_COS => neg(mul(mul(apply(EXP_UNIT_NAME_SIN, a), radians()), da)),
+        EXP_UNIT_NAME_TAN => div(mul(radians(), da), apply(EXP_UNIT_NAME_SQR, apply(EXP_UNIT_NAME_COS, a))),
+        EXP_UNIT_NAME_ASIN => div(da, mul(radians(), apply(EXP_UNIT_NAME_SQRT, sub(number(1), apply(EXP_UNIT_NAME_SQR, a))))),
+        EXP_UNIT_NAME_ACOS => neg(div(da, mul(radians(), apply(EXP_UNIT_NAME_SQRT, sub(number(1), apply(EXP_UNIT_NAME_SQR, a)))))),
+        EXP_UNIT_NAME_ATAN => div(da, mul(radians(), add(number(1), apply(EXP_UNIT_NAME_SQR, a)))),
+        EXP_UNIT_NAME_SINH => mul(apply(EXP_UNIT_NAME_COSH, a), da),
+        EXP_UNIT_NAME_COSH => mul(apply(EXP_UNIT_NAME_SINH, a), da),
+        EXP_UNIT_NAME_TANH => div(da, apply(EXP_UNIT_NAME_SQR, apply(EXP_UNIT_NAME_COSH, a))),
+        EXP_UNIT_NAME_ASINH => div(da, apply(EXP_UNIT_NAME_SQRT, add(apply(EXP_UNIT_NAME_SQR, a), number(1)))),
+        EXP_UNIT_NAME_ACOSH => div(da, apply(EXP_UNIT_NAME_SQRT, sub(apply(EXP_UNIT_NAME_SQR, a), number(1)))),
+        EXP_UNIT_NAME_ATANH => div(da, sub(number(1), apply(EXP_UNIT_NAME_SQR, a))),
+        // a user function is differentiated through its body
+        _ => match Context::with_current(|c| c.borrow().functions.user_function(name)) {
+            // a recursive function has no body without calls to itself
+            Some(def) if def.params.len() == args.len() && !calls.iter().any(|call| call == name) => {
+                let body = def.body.to_term().ok_or(CalcError::EmptyExpression)?;
+                calls.push(name.to_string());
+                let d = derive(&substitute(body, args), x, calls);
+                calls.pop();
+                return d;
+            },
+            _ => return Err(CalcError::NotDifferentiable { name: name.to_string() }),
+        },
+    };
+    Ok(d)
+}
+
+// whether a term changes with the variable, the user functions are assumed to use their parameters
+fn depends_on(term: &Term, x: &str) -> bool {
+    match term {
+        Term::Constant { .. } => false,
+        Term::Variable(name) | Term::Parameter(name, _) => name == x,
+        Term::Apply(_, args) => args.iter().any(|arg| depends_on(arg, x)),
+    }
+}
+
+// body of a user function with its parameters replaced by the arguments of the call
+fn substitute(body: Term, args: &[Term]) -> Term {
+    match body {
+        Term::Parameter(_, index) => args[index].clone(),
+        Term::Apply(name, operands) => Term::Apply(name, operands.into_iter().map(|op| substitute(op, args)).collect()),
+        term => term,
+    }
+}
+
+fn number(v: i64) -> Term {
+    Term::number(Number::from(v))
+}
+
+fn is_zero(term: &Term) -> bool {
+    matches!(term, Term::Constant { value, name: None, .. } if value.is_zero())
+}
+
+fn apply(name: &str, a: Term) -> Term {
+    Term::apply(name, vec![a])
+}
+
+// the terms of a rule which have a derivative of 0 as a factor are left out, e.g. (3x)' is 3 and not 0×x+3×1,
+// the other factors are parts of the expression, so they are already defined where the derivative is
+
+fn add(a: Term, b: Term) -> Term {
+    match (is_zero(&a), is_zero(&b)) {
+        (true, _) => b,
+        (_, true) => a,
+        _ => Term::apply(EXP_UNIT_NAME_ADD, vec![a, b]),
+    }
+}
+
+fn sub(a: Term, b: Term) -> Term {
+    match (is_zero(&a), is_zero(&b)) {
+        (_, true) => a,
+        (true, _) => neg(b),
+        _ => Term::apply(EXP_UNIT_NAME_SUB, vec![a, b]),
+    }
+}
+
+fn mul(a: Term, b: Term) -> Term {
+    if is_zero(&a) || is_zero(&b) {
+        return number(0);
+    }
+    Term::apply(EXP_UNIT_NAME_MUL, vec![a, b])
+}
+
+fn div(a: Term, b: Term) -> Term {
+    if is_zero(&a) {
+        return number(0);
+    }
+    Term::apply(EXP_UNIT_NAME_DIV, vec![a, b])
+}
+
+fn neg(a: Term) -> Term {
+    if is_zero(&a) {
+        return a;
+    }
+    Term::apply(EXP_UNIT_NAME_NEG, vec![a])
+}
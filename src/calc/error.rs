@@ -0,0 +1,92 @@
+/// errors of building and executing expressions, exp_idx is the position of the offending token
+#[derive(Clone, Debug, PartialEq)]
+pub enum CalcError {
+    // a token which is not allowed where it appears, an empty token is the end of the input
+    Syntax { token: String, exp_idx: i32 },
+    // an operator or a function without all of its operands
+    MissingOperand { exp_idx: i32 },
+    MismatchedBrackets { exp_idx: i32 },
+    // an argument outside of the domain of the function
+    Domain { exp_idx: i32 },
+    DivisionByZero { exp_idx: i32 },
+    Overflow { exp_idx: i32 },
+    // an operand with a fraction part in the programmer mode
+    NotAnInteger { exp_idx: i32 },
+    UnknownFunction { name: String, exp_idx: i32 },
+    UnknownVariable { name: String, exp_idx: i32 },
+    ArgumentCount { name: String, count: i32, min: i32, max: i32, exp_idx: i32 },
+    RecursionLimit { depth: usize, exp_idx: i32 },
+    // a numerical result which does not reach the requested accuracy
+    ToleranceNotReached { exp_idx: i32 },
+    // a name which cannot be used for a variable, a function or a parameter
+    InvalidName { name: String },
+    // a name which is already taken by a constant, a function or a variable
+    NameInUse { name: String },
+    // a function which has no derivative formula
+    NotDifferentiable { name: String },
+    EmptyExpression,
+}
+
+impl CalcError {
+    pub fn exp_idx(&self) -> Option<i32> {
+        match self {
+            CalcError::Syntax { exp_idx, .. } |
+            CalcError::MissingOperand { exp_idx } |
+            CalcError::MismatchedBrackets { exp_idx } |
+            CalcError::Domain { exp_idx } |
+            CalcError::DivisionByZero { exp_idx } |
+            CalcError::Overflow { exp_idx } |
+            CalcError::NotAnInteger { exp_idx } |
+            CalcError::UnknownFunction { exp_idx, .. } |
+            CalcError::UnknownVariable { exp_idx, .. } |
+            CalcError::ArgumentCount { exp_idx, .. } |
+            CalcError::RecursionLimit { exp_idx, .. } |
+            CalcError::ToleranceNotReached { exp_idx } => Some(*exp_idx),
+            CalcError::InvalidName { .. } |
+            CalcError::NameInUse { .. } |
+            CalcError::NotDifferentiable { .. } |
+            CalcError::EmptyExpression => None,
+        }
+    }
+}
+
+impl std::fmt::Display for CalcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let plural = |n: &i32| if *n == 1 { "" } else { "s" };
+        match self {
+            CalcError::Syntax { token, .. } => {
+                if token.is_empty() {
+                    write!(f, "Unexpected end of expression")
+                }
+                else {
+                    write!(f, "Unexpected '{}'", token)
+                }
+            },
+            CalcError::MissingOperand { .. } => write!(f, "Missing operand"),
+            CalcError::MismatchedBrackets { .. } => write!(f, "Mismatched brackets"),
+            CalcError::Domain { .. } => write!(f, "Invalid input"),
+            CalcError::DivisionByZero { .. } => write!(f, "Division by zero"),
+            CalcError::Overflow { .. } => write!(f, "Overflow"),
+            CalcError::NotAnInteger { .. } => write!(f, "Not an integer"),
+            CalcError::UnknownFunction { name, .. } => write!(f, "Unknown function '{}'", name),
+            CalcError::UnknownVariable { name, .. } => write!(f, "Unknown variable '{}'", name),
+            CalcError::ArgumentCount { name, count, min, max, .. } => {
+                if min == max {
+                    write!(f, "{} expects {} argument{}", name, min, plural(min))
+                }
+                else if count < min {
+                    write!(f, "{} expects at least {} argument{}", name, min, plural(min))
+                }
+                else {
+                    write!(f, "{} expects at most {} argument{}", name, max, plural(max))
+                }
+            },
+            CalcError::RecursionLimit { depth, .. } => write!(f, "Recursion deeper than {} calls", depth),
+            CalcError::ToleranceNotReached { .. } => write!(f, "Tolerance not reached"),
+            CalcError::InvalidName { name } => write!(f, "Invalid name '{}'", name),
+            CalcError::NameInUse { name } => write!(f, "'{}' is already used", name),
+            CalcError::NotDifferentiable { name } => write!(f, "Cannot differentiate '{}'", name),
+            CalcError::EmptyExpression => write!(f, "Empty expression"),
+        }
+    }
+}
@@ -1,6 +1,13 @@
 use core::borrow;
-use std::{borrow::Borrow, collections::HashMap};
+use std::{borrow::Borrow, collections::HashMap, rc::Rc};
 use super::functions::*;
+use super::context::{Context, ImplicitMulPrecedence};
+use super::error::CalcError;
+use super::complex;
+use super::math;
+use super::number::{Number, MAX_EXPONENT};
+use super::parser::Lexer;
+use super::simplify::Term;
 use lazy_static::lazy_static;
 
 pub const EXP_UNIT_NAME_CONSTANT: &str = "constant";
@@ -8,14 +15,64 @@ pub const EXP_UNIT_NAME_ADD: &str = "+";
 pub const EXP_UNIT_NAME_SUB: &str = "−";
 pub const EXP_UNIT_NAME_MUL: &str = "×";
 pub const EXP_UNIT_NAME_DIV: &str = "÷";
+pub const EXP_UNIT_NAME_NEG: &str = "neg";
 pub const EXP_UNIT_NAME_SIN: &str = "sin";
 pub const EXP_UNIT_NAME_COS: &str = "cos";
 pub const EXP_UNIT_NAME_TAN: &str = "tan";
+pub const EXP_UNIT_NAME_ASIN: &str = "asin";
+pub const EXP_UNIT_NAME_ACOS: &str = "acos";
+pub const EXP_UNIT_NAME_ATAN: &str = "atan";
+pub const EXP_UNIT_NAME_ATAN2: &str = "atan2";
+pub const EXP_UNIT_NAME_SINH: &str = "sinh";
+pub const EXP_UNIT_NAME_COSH: &str = "cosh";
+pub const EXP_UNIT_NAME_TANH: &str = "tanh";
+pub const EXP_UNIT_NAME_ASINH: &str = "asinh";
+pub const EXP_UNIT_NAME_ACOSH: &str = "acosh";
+pub const EXP_UNIT_NAME_ATANH: &str = "atanh";
 pub const EXP_UNIT_NAME_INV: &str = "⅟";
 pub const EXP_UNIT_NAME_SQR: &str = "²";
+pub const EXP_UNIT_NAME_FACTORIAL: &str = "!";
+pub const EXP_UNIT_NAME_DOUBLE_FACTORIAL: &str = "!!";
+pub const EXP_UNIT_NAME_GAMMA: &str = "Γ";
+pub const EXP_UNIT_NAME_PERMUTATIONS: &str = "nPr";
+pub const EXP_UNIT_NAME_COMBINATIONS: &str = "nCr";
 pub const EXP_UNIT_NAME_SQRT: &str = "√";
+pub const EXP_UNIT_NAME_LN: &str = "ln";
+pub const EXP_UNIT_NAME_LOG10: &str = "log10";
+// short name of log10
+pub const EXP_UNIT_NAME_LOG: &str = "log";
+pub const EXP_UNIT_NAME_LOG2: &str = "log2";
+pub const EXP_UNIT_NAME_LOGB: &str = "logb";
+pub const EXP_UNIT_NAME_EXP: &str = "exp";
+pub const EXP_UNIT_NAME_EXP10: &str = "10ˣ";
+pub const EXP_UNIT_NAME_ABS: &str = "abs";
+pub const EXP_UNIT_NAME_POW: &str = "^";
+pub const EXP_UNIT_NAME_ROOT: &str = "ʸ√";
+pub const EXP_UNIT_NAME_MOD: &str = "mod";
+pub const EXP_UNIT_NAME_INT_DIV: &str = "div";
+pub const EXP_UNIT_NAME_REM: &str = "rem";
 pub const EXP_UNIT_NAME_OPEN_BRK: &str = "(";
 pub const EXP_UNIT_NAME_CLOSE_BRK: &str = ")";
+pub const EXP_UNIT_NAME_COMMA: &str = ",";
+pub const EXP_UNIT_NAME_MIN: &str = "min";
+pub const EXP_UNIT_NAME_MAX: &str = "max";
+pub const EXP_UNIT_NAME_HYPOT: &str = "hypot";
+pub const EXP_UNIT_NAME_GCD: &str = "gcd";
+pub const EXP_UNIT_NAME_SUM: &str = "sum";
+pub const EXP_UNIT_NAME_MULTINOMIAL: &str = "multinomial";
+pub const EXP_UNIT_NAME_RE: &str = "re";
+pub const EXP_UNIT_NAME_IM: &str = "im";
+pub const EXP_UNIT_NAME_ARG: &str = "arg";
+pub const EXP_UNIT_NAME_CONJ: &str = "conj";
+pub const EXP_UNIT_NAME_AND: &str = "AND";
+pub const EXP_UNIT_NAME_OR: &str = "OR";
+pub const EXP_UNIT_NAME_XOR: &str = "XOR";
+pub const EXP_UNIT_NAME_NOT: &str = "NOT";
+pub const EXP_UNIT_NAME_SHL: &str = "<<";
+pub const EXP_UNIT_NAME_SHR: &str = ">>";
+pub const EXP_UNIT_NAME_ROL: &str = "ROL";
+pub const EXP_UNIT_NAME_ROR: &str = "ROR";
+pub const EXP_UNIT_NAME_INTEGRAL: &str = "∫";
 
 
 //// structures
@@ -24,9 +81,9 @@ pub struct Expression {
 }
 
 impl Expression {
-    pub fn execute(&self) -> Result<f64, String> {
+    pub fn execute(&self) -> Result<Number, CalcError> {
         if self.root.is_none() {
-            return Err("Empty expression".to_string());
+            return Err(CalcError::EmptyExpression);
         }
         self.root.as_ref().unwrap().execute()
     }
@@ -37,6 +94,74 @@ impl Expression {
         }
         self.root.as_ref().unwrap().to_string()
     }
+
+    /// the expression as a term, None when it is empty
+    pub fn to_term(&self) -> Option<Term> {
+        self.root.as_ref().map(|root| root.to_term())
+    }
+
+    /// expression of a term, with brackets where the precedence of the operators needs them
+    pub fn from_term(term: &Term) -> Result<Expression, CalcError> {
+        let (root, _) = build_term(term)?;
+        Ok(Expression { root: Some(root) })
+    }
+}
+
+// unit of a term and the precedence it binds with, i32::MIN for the units which never need brackets
+fn build_term(term: &Term) -> Result<(Box<dyn ExcutableUnit>, i32), CalcError> {
+    let (name, args) = match term {
+        Term::Constant { value, name, literal } => {
+            let mut unit = ConstantUnit::new(value.clone());
+            unit.name = name.clone();
+            unit.literal = literal.clone();
+            // a negative number binds as a negation, e.g. (-3)²
+            let precedence = if name.is_none() && value.is_negative() { PRIODITY_UNARY_OP } else { i32::MIN };
+            return Ok((Box::new(unit), precedence));
+        },
+        Term::Variable(name) => return Ok((Box::new(VariableUnit::new(name.clone())), i32::MIN)),
+        Term::Parameter(name, index) => return Ok((Box::new(ParameterUnit::new(name.clone(), *index)), i32::MIN)),
+        Term::Apply(name, args) => (name, args),
+    };
+
+    // a number times a name is written without the multiplication sign, e.g. 2x
+    let implicit = name == EXP_UNIT_NAME_MUL
+        && matches!(args.as_slice(), [Term::Constant { name: None, .. }, Term::Variable(_) | Term::Parameter(..)]);
+    let mut op = if implicit {
+        ImplicitMulOperator::create()
+    }
+    else {
+        let op = Context::with_current(|c| c.borrow().functions.get_functor(name));
+        op.ok_or(CalcError::UnknownFunction { name: name.clone(), exp_idx: -1 })?
+    };
+    if op.takes_argument_list() {
+        op.set_arg_count(args.len() as i32);
+    }
+    let precedence = op.get_op_base().precedence;
+    let infix = op.arg_count() == 2 && !op.takes_argument_list();
+    let negation = name == EXP_UNIT_NAME_NEG;
+    // a negated product has the same value without brackets, e.g. −2x, unlike a negated −(x nCr 2),
+    // the other functions write their arguments in brackets themselves
+    let bound = if negation { PRIODITY_MULTIPLICATIVE } else if infix || op.is_postfix() { precedence } else { i32::MAX };
+    let mut binding = precedence;
+
+    // operands are pushed from the last one to the first one
+    for (i, arg) in args.iter().enumerate().rev() {
+        let (unit, child) = build_term(arg)?;
+        let wrap = child > bound || (negation && child == PRIODITY_COMBINATORIAL) || (infix && child == bound && (i == 1) != op.is_right_associative());
+        if wrap {
+            let mut bracket = CollectOperator::new();
+            bracket.base._1 = Some(unit);
+            op.push_operand(Box::new(bracket));
+        }
+        else {
+            // the negation then binds as the product, e.g. 1÷(−2x)
+            if negation {
+                binding = binding.max(child);
+            }
+            op.push_operand(unit);
+        }
+    }
+    Ok((op.as_excutable_unit(), if infix || negation { binding } else { i32::MIN }))
 }
 
 struct ExpUnitBase {
@@ -68,24 +193,49 @@ struct UnaryFunctionBase {
     _1: Option<Box<dyn ExcutableUnit>>,
 }
 
+struct VariadicFunctionBase {
+    unitbase: ExpOpBase,
+    args: Vec<Box<dyn ExcutableUnit>>,
+    arg_count: i32,
+    min_args: i32,
+    max_args: i32,
+}
+
 
 struct ConstantUnit {
     pub unitbase: ExpUnitBase,
-    pub value: f64,
+    pub value: Number,
+    // name of a named constant such as π
+    pub name: Option<String>,
+    // the number as it was written, when its value is displayed differently, e.g. 0xFF or 6.022e23
+    pub literal: Option<String>,
 }
 
 impl ConstantUnit {
-    pub fn new(value: f64) -> Self {
+    pub fn new(value: Number) -> Self {
         Self {
             unitbase: ExpUnitBase::new(),
             value,
+            name: None,
+            literal: None,
         }
     }
 }
 
 impl ExcutableUnit for ConstantUnit {
-    fn execute(&self) -> Result<f64, String> {
-        Ok(self.value)
+    fn execute(&self) -> Result<Number, CalcError> {
+        let (fraction_mode, integer_mode) = Context::with_current(|c| (c.borrow().fraction_mode, c.borrow().integer_mode.is_some()));
+        // the programmer mode takes integers only, it does not drop the fraction part of an operand
+        if integer_mode && !self.value.is_integer() {
+            return Err(CalcError::NotAnInteger { exp_idx: self.get_exp_idx() });
+        }
+        // numbers typed in the fraction mode are exact, named constants stay decimals
+        let value = if fraction_mode && self.name.is_none() { self.value.to_fraction() } else { self.value.clone() };
+        value.round_result(self.get_exp_idx())
+    }
+
+    fn to_term(&self) -> Term {
+        Term::Constant { value: self.value.clone(), name: self.name.clone(), literal: self.literal.clone() }
     }
 }
 
@@ -101,17 +251,134 @@ pub trait ExpUnit {
     fn set_exp_idx(&mut self, idx: i32) {
         self.get_exp_unit_base_mut().exp_idx = idx;
     }
+    // a single number or name, a postfix operator follows it without brackets
+    fn is_leaf(&self) -> bool {
+        false
+    }
 }
 
 impl ExpUnit for ConstantUnit {
     fn to_string(&self) -> String {
-        self.value.to_string()
+        if let Some(literal) = &self.literal {
+            return literal.clone();
+        }
+        match &self.name {
+            Some(name) => name.clone(),
+            // fractions and complex numbers are bracketed, so they stay one operand when the expression is read back
+            None if self.value.is_complex() || (self.value.is_fraction() && !self.value.is_integer()) => format!("({})", self.value),
+            None => self.value.to_string(),
+        }
     }
 
     fn exp_name(&self) -> &str {
         EXP_UNIT_NAME_CONSTANT
     }
 
+    fn is_leaf(&self) -> bool {
+        true
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.unitbase
+    }
+}
+
+
+/// named variable, its value is looked up whenever the expression is executed
+struct VariableUnit {
+    pub unitbase: ExpUnitBase,
+    pub name: String,
+}
+
+impl VariableUnit {
+    pub fn new(name: String) -> Self {
+        Self {
+            unitbase: ExpUnitBase::new(),
+            name,
+        }
+    }
+}
+
+impl ExcutableUnit for VariableUnit {
+    fn execute(&self) -> Result<Number, CalcError> {
+        let value = Context::with_current(|c| c.borrow().variables.get(&self.name).cloned());
+        value.ok_or(CalcError::UnknownVariable { name: self.name.clone(), exp_idx: self.get_exp_idx() })
+    }
+
+    fn to_term(&self) -> Term {
+        Term::Variable(self.name.clone())
+    }
+}
+
+impl ExpUnit for VariableUnit {
+    fn to_string(&self) -> String {
+        self.name.clone()
+    }
+
+    fn exp_name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_leaf(&self) -> bool {
+        true
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.unitbase
+    }
+}
+
+/// parameter of a user function, its value is taken from the innermost call
+struct ParameterUnit {
+    pub unitbase: ExpUnitBase,
+    pub name: String,
+    pub index: usize,
+}
+
+impl ParameterUnit {
+    pub fn new(name: String, index: usize) -> Self {
+        Self {
+            unitbase: ExpUnitBase::new(),
+            name,
+            index,
+        }
+    }
+}
+
+impl ExcutableUnit for ParameterUnit {
+    fn execute(&self) -> Result<Number, CalcError> {
+        let value = Context::with_current(|c| {
+            c.borrow().call_frames.last().and_then(|frame| frame.get(self.index).cloned())
+        });
+        value.ok_or(CalcError::UnknownVariable { name: self.name.clone(), exp_idx: self.get_exp_idx() })
+    }
+
+    fn to_term(&self) -> Term {
+        Term::Parameter(self.name.clone(), self.index)
+    }
+}
+
+impl ExpUnit for ParameterUnit {
+    fn to_string(&self) -> String {
+        self.name.clone()
+    }
+
+    fn exp_name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_leaf(&self) -> bool {
+        true
+    }
+
     fn get_exp_unit_base(&self) -> &ExpUnitBase {
         &self.unitbase
     }
@@ -151,8 +418,43 @@ impl UnaryFunctionBase {
     }
 }
 
+impl VariadicFunctionBase {
+    pub fn new(id: FunctionId, precedence: i32, min_args: i32, max_args: i32) -> Self {
+        Self {
+            unitbase: ExpOpBase {
+                unitbase: ExpUnitBase::new(),
+                id,
+                precedence
+            },
+            args: Vec::new(),
+            arg_count: min_args,
+            min_args,
+            max_args,
+        }
+    }
+}
+
+// write two operands next to each other, separated only when they would be read as one token
+fn juxtapose(left: &str, right: &str) -> String {
+    let is_word = |c: char| c.is_alphabetic() || c.is_ascii_digit() || c == '_' || c == '.';
+    let is_digit = |c: char| c.is_ascii_digit() || c == '.';
+
+    // a number followed by a name still reads as two tokens, e.g. 2a
+    let last_word: String = left.chars().rev().take_while(|c| is_word(*c)).collect();
+    let joined = !last_word.is_empty() && right.starts_with(is_word);
+    let number_and_name = last_word.chars().all(is_digit) && !right.starts_with(is_digit);
+    if joined && !number_and_name {
+        format!("{} {}", left, right)
+    }
+    else {
+        format!("{}{}", left, right)
+    }
+}
+
 pub trait ExcutableUnit : ExpUnit {
-    fn execute(&self) -> Result<f64, String>;    
+    fn execute(&self) -> Result<Number, CalcError>;    
+    // the unit and its operands as a term, for the passes which rewrite expressions
+    fn to_term(&self) -> Term;
 }
 
 pub trait ExpOpUnit : ExcutableUnit {
@@ -164,12 +466,70 @@ pub trait ExpOpUnit : ExcutableUnit {
     fn is_operator(&self) -> bool {
         false
     }
+    fn is_postfix(&self) -> bool {
+        false
+    }
+    fn is_right_associative(&self) -> bool {
+        false
+    }
+    // functions which take the comma separated values between their brackets as arguments
+    fn takes_argument_list(&self) -> bool {
+        false
+    }
+    // minimum and maximum number of arguments
+    fn arg_count_range(&self) -> (i32, i32) {
+        (self.arg_count(), self.arg_count())
+    }
+    fn set_arg_count(&mut self, _count: i32) {
+    }
+    // store an argument completed by a comma, returns false if the unit does not collect arguments
+    fn push_argument(&mut self, _arg: Box<dyn ExcutableUnit>) -> bool {
+        false
+    }
+    fn has_arguments(&self) -> bool {
+        false
+    }
+    fn take_arguments(&mut self) -> Vec<Box<dyn ExcutableUnit>> {
+        Vec::new()
+    }
+    // functions which are defined for complex arguments, the others fail on them
+    fn accepts_complex(&self) -> bool {
+        false
+    }
+    // functions which take ∞ and NaN as they are, the others give the result of execute_f64 for them
+    fn accepts_non_finite(&self) -> bool {
+        false
+    }
+}
+
+// value of an operation which failed or has ∞ or NaN as an argument, with the IEEE passthrough: the result
+// of the operation on floating point numbers, or NaN when there is none; without the passthrough it fails
+fn ieee_result<F>(error: CalcError, f: F) -> Result<Number, CalcError>
+where F: FnOnce() -> Option<f64> {
+    let passthrough = Context::with_current(|c| {
+        let c = c.borrow();
+        c.ieee_passthrough && c.integer_mode.is_none()
+    });
+    if !passthrough {
+        return Err(error);
+    }
+    let default = match error {
+        CalcError::Overflow { .. } => f64::INFINITY,
+        CalcError::Domain { .. } | CalcError::DivisionByZero { .. } => f64::NAN,
+        _ => return Err(error),
+    };
+    Ok(Number::from_f64_value(f().unwrap_or(default)))
 }
 
 pub trait BinaryFunctionUnit: ExpOpUnit {
     fn get_func_base(&self) -> &BinaryFunctionBase;
     fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase;
-    fn execute_with_args(&self, _1: f64, _2: f64) -> Result<f64, String>;
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError>;
+
+    // the operation on floating point numbers, for the results of the IEEE passthrough
+    fn execute_f64(&self, _1: f64, _2: f64) -> Option<f64> {
+        None
+    }
 
     fn get_op_base(&self) -> &ExpOpBase {
         &self.get_func_base().unitbase
@@ -194,11 +554,11 @@ pub trait BinaryFunctionUnit: ExpOpUnit {
         return -1;
     }
 
-    fn execute(&self) -> Result<f64, String> {
+    fn execute(&self) -> Result<Number, CalcError> {
         let base = self.get_func_base();
 
         if base._1.is_none() || base._2.is_none() {
-            return Err("Missing operand".to_string());
+            return Err(CalcError::MissingOperand { exp_idx: self.get_exp_idx() });
         }
         
         let res1 = base._1.as_ref().unwrap().execute();
@@ -211,7 +571,21 @@ pub trait BinaryFunctionUnit: ExpOpUnit {
             return res2;
         }
 
-        self.execute_with_args(res1.unwrap(), res2.unwrap())
+        let (_1, _2) = (res1.unwrap(), res2.unwrap());
+        if !self.accepts_complex() && (_1.is_complex() || _2.is_complex()) {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        let (x, y) = (_1.to_f64(), _2.to_f64());
+        if (!_1.is_finite() || !_2.is_finite()) && !self.accepts_non_finite() {
+            return ieee_result(CalcError::Domain { exp_idx: self.get_exp_idx() }, || self.execute_f64(x, y))?.round_result(self.get_exp_idx());
+        }
+        self.execute_with_args(_1, _2).or_else(|e| ieee_result(e, || self.execute_f64(x, y)))?.round_result(self.get_exp_idx())
+    }
+
+    fn to_term(&self) -> Term {
+        let base = self.get_func_base();
+        let operands = [&base._1, &base._2].into_iter().flatten().map(|op| op.to_term()).collect();
+        Term::Apply(self.exp_name().to_string(), operands)
     }
 
     fn to_string(&self) -> String {
@@ -219,16 +593,24 @@ pub trait BinaryFunctionUnit: ExpOpUnit {
         let _1 = &base._1;
         let _2 = &base._2;
 
+        // operators spelled as words (mod, div, etc.) are separated from their operands
+        let name = if Lexer::is_identifier(self.exp_name()) {
+            format!(" {} ", self.exp_name())
+        }
+        else {
+            self.exp_name().to_string()
+        };
+
         match _1 {
             None => {
-                self.exp_name().to_string()
+                name
             },
             Some(op_1) => {
                 if _2.is_none() {
-                    return op_1.to_string() + self.exp_name();
+                    return op_1.to_string() + &name;
                 }
                 else {
-                    return op_1.to_string() + self.exp_name() + _2.as_ref().unwrap().to_string().borrow();
+                    return op_1.to_string() + &name + _2.as_ref().unwrap().to_string().borrow();
                 }
             }
         }
@@ -242,7 +624,12 @@ pub trait BinaryFunctionUnit: ExpOpUnit {
 pub trait UnaryFunctionUnit: ExpOpUnit {
     fn get_func_base(&self) -> &UnaryFunctionBase;
     fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase;
-    fn execute_with_args(&self, _1: f64) -> Result<f64, String>;
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError>;
+
+    // the function on floating point numbers, for the results of the IEEE passthrough
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        None
+    }
 
     fn get_op_base(&self) -> &ExpOpBase {
         &self.get_func_base().unitbase
@@ -262,11 +649,11 @@ pub trait UnaryFunctionUnit: ExpOpUnit {
         return -1;
     }
 
-    fn execute(&self) -> Result<f64, String> {
+    fn execute(&self) -> Result<Number, CalcError> {
         let base = self.get_func_base();
 
         if base._1.is_none() {
-            return Err("Missing operand".to_string());
+            return Err(CalcError::MissingOperand { exp_idx: self.get_exp_idx() });
         }
         
         let res1 = base._1.as_ref().unwrap().execute();
@@ -274,7 +661,20 @@ pub trait UnaryFunctionUnit: ExpOpUnit {
             return res1;
         }
 
-        self.execute_with_args(res1.unwrap())
+        let _1 = res1.unwrap();
+        if !self.accepts_complex() && _1.is_complex() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        let x = _1.to_f64();
+        if !_1.is_finite() && !self.accepts_non_finite() {
+            return ieee_result(CalcError::Domain { exp_idx: self.get_exp_idx() }, || self.execute_f64(x))?.round_result(self.get_exp_idx());
+        }
+        self.execute_with_args(_1).or_else(|e| ieee_result(e, || self.execute_f64(x)))?.round_result(self.get_exp_idx())
+    }
+
+    fn to_term(&self) -> Term {
+        let operands = self.get_func_base()._1.iter().map(|op| op.to_term()).collect();
+        Term::Apply(self.exp_name().to_string(), operands)
     }
 
     fn to_string(&self) -> String {
@@ -301,14 +701,90 @@ pub trait UnaryFunctionUnit: ExpOpUnit {
     }
 }
 
+trait VariadicFunctionUnit: ExpOpUnit {
+    fn get_func_base(&self) -> &VariadicFunctionBase;
+    fn get_func_base_mut(&mut self) -> &mut VariadicFunctionBase;
+    fn execute_with_args(&self, args: &[Number]) -> Result<Number, CalcError>;
+
+    fn get_op_base(&self) -> &ExpOpBase {
+        &self.get_func_base().unitbase
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        &mut self.get_func_base_mut().unitbase
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        let base = self.get_func_base_mut();
+
+        // operands are pushed from the last argument to the first one
+        if base.args.len() as i32 >= base.arg_count {
+            return -1;
+        }
+        base.args.insert(0, operand);
+        base.arg_count - base.args.len() as i32
+    }
+
+    fn execute(&self) -> Result<Number, CalcError> {
+        let base = self.get_func_base();
+
+        if (base.args.len() as i32) < base.arg_count {
+            return Err(CalcError::MissingOperand { exp_idx: self.get_exp_idx() });
+        }
+
+        let mut values = Vec::with_capacity(base.args.len());
+        for arg in &base.args {
+            values.push(arg.execute()?);
+        }
+
+        if !self.accepts_complex() && values.iter().any(|v| v.is_complex()) {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        if values.iter().any(|v| !v.is_finite()) && !self.accepts_non_finite() {
+            return ieee_result(CalcError::Domain { exp_idx: self.get_exp_idx() }, || None)?.round_result(self.get_exp_idx());
+        }
+        self.execute_with_args(&values).or_else(|e| ieee_result(e, || None))?.round_result(self.get_exp_idx())
+    }
+
+    fn to_term(&self) -> Term {
+        let operands = self.get_func_base().args.iter().map(|arg| arg.to_term()).collect();
+        Term::Apply(self.exp_name().to_string(), operands)
+    }
+
+    fn to_string(&self) -> String {
+        let base = self.get_func_base();
+
+        if base.args.is_empty() {
+            return self.exp_name().to_string();
+        }
+        let args: Vec<String> = base.args.iter().map(|arg| arg.to_string()).collect();
+        format!("{}({})", self.exp_name(), args.join(", "))
+    }
+
+    fn arg_count(&self) -> i32 {
+        self.get_func_base().arg_count
+    }
+
+    fn arg_count_range(&self) -> (i32, i32) {
+        (self.get_func_base().min_args, self.get_func_base().max_args)
+    }
+
+    fn set_arg_count(&mut self, count: i32) {
+        self.get_func_base_mut().arg_count = count;
+    }
+}
+
 struct CollectOperator {
     base: UnaryFunctionBase,
+    // arguments before the last comma inside the brackets
+    args: Vec<Box<dyn ExcutableUnit>>,
 }
 
 impl CollectOperator {
     pub fn new() -> Self {
         Self {
             base: UnaryFunctionBase::new(ID_OPEN_BRACKET, 999),
+            args: Vec::new(),
         }
     }    
 }
@@ -322,7 +798,7 @@ impl UnaryFunctionUnit for CollectOperator {
         &mut self.base
     }
 
-    fn execute_with_args(&self, _1: f64) -> Result<f64, String> {
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
         Ok(_1)
     }
 }
@@ -347,6 +823,7 @@ impl ExpOpUnit for CollectOperator {
     fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
         let mut new_instance = CollectOperator::new();
         new_instance.base._1 = self.base._1.take();
+        new_instance.args = std::mem::take(&mut self.args);
         new_instance.set_exp_idx(self.get_exp_idx());
         Box::new(new_instance)
     }
@@ -354,12 +831,40 @@ impl ExpOpUnit for CollectOperator {
     fn is_operator(&self) -> bool {
         true
     }
+
+    fn push_argument(&mut self, arg: Box<dyn ExcutableUnit>) -> bool {
+        self.args.push(arg);
+        true
+    }
+
+    fn has_arguments(&self) -> bool {
+        !self.args.is_empty()
+    }
+
+    fn take_arguments(&mut self) -> Vec<Box<dyn ExcutableUnit>> {
+        std::mem::take(&mut self.args)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
 }
 
 impl ExcutableUnit for CollectOperator {
-    fn execute(&self) -> Result<f64, String> {
+    fn execute(&self) -> Result<Number, CalcError> {
         UnaryFunctionUnit::execute(self)
     }
+
+    // brackets are left out of the terms, they are put back where they are needed
+    fn to_term(&self) -> Term {
+        match &self.base._1 {
+            Some(op_1) => op_1.to_term(),
+            None => Term::Apply(self.exp_name().to_string(), Vec::new()),
+        }
+    }
 }
 
 impl ExpUnit for CollectOperator {
@@ -367,12 +872,18 @@ impl ExpUnit for CollectOperator {
         let base = self.get_func_base();
         let _1 = &base._1;
 
+        let mut args = String::new();
+        for arg in &self.args {
+            args += &arg.to_string();
+            args += ", ";
+        }
+
         match _1 {
             None => {
-                "(".to_string()
+                format!("({}", args)
             },
             Some(op_1) => {                
-                format!("({})", op_1.to_string())
+                format!("({}{})", args, op_1.to_string())
             }
         }
     }
@@ -390,6 +901,11 @@ impl ExpUnit for CollectOperator {
     }
 }
 
+// functions give complex results where they have no real ones
+fn complex_mode() -> bool {
+    Context::with_current(|c| c.borrow().complex_mode)
+}
+
 /// sin function
 struct SinFunc {
     base: UnaryFunctionBase,
@@ -412,8 +928,13 @@ impl UnaryFunctionUnit for SinFunc {
         &mut self.base
     }
 
-    fn execute_with_args(&self, _1: f64) -> Result<f64, String> {
-        Ok(_1.sin())
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        let mode = Context::with_current(|c| c.borrow().angle_mode);
+        let digits = Number::working_digits();
+        if _1.is_complex() {
+            return complex::sin(&math::to_radians(&_1, mode, digits), digits).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() });
+        }
+        Ok(math::sin(&_1, mode, digits))
     }
 }
 
@@ -440,12 +961,20 @@ impl ExpOpUnit for SinFunc {
         new_instance.set_exp_idx(self.get_exp_idx());
         Box::new(new_instance)
     }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
 }
 
 impl ExcutableUnit for SinFunc {
-    fn execute(&self) -> Result<f64, String> {
+    fn execute(&self) -> Result<Number, CalcError> {
         UnaryFunctionUnit::execute(self)
     }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
 }
 
 impl ExpUnit for SinFunc {
@@ -488,8 +1017,13 @@ impl UnaryFunctionUnit for CosFunc {
         &mut self.base
     }
 
-    fn execute_with_args(&self, _1: f64) -> Result<f64, String> {
-        Ok(_1.cos())
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        let mode = Context::with_current(|c| c.borrow().angle_mode);
+        let digits = Number::working_digits();
+        if _1.is_complex() {
+            return complex::cos(&math::to_radians(&_1, mode, digits), digits).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() });
+        }
+        Ok(math::cos(&_1, mode, digits))
     }
 }
 
@@ -517,12 +1051,20 @@ impl ExpOpUnit for CosFunc {
         new_instance.set_exp_idx(self.get_exp_idx());
         Box::new(new_instance)
     }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
 }
 
 impl ExcutableUnit for CosFunc {
-    fn execute(&self) -> Result<f64, String> {
+    fn execute(&self) -> Result<Number, CalcError> {
         UnaryFunctionUnit::execute(self)
     }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
 }
 
 impl ExpUnit for CosFunc {
@@ -565,8 +1107,20 @@ impl UnaryFunctionUnit for TanFunc {
         &mut self.base
     }
 
-    fn execute_with_args(&self, _1: f64) -> Result<f64, String> {
-        Ok(_1.tan())
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        let mode = Context::with_current(|c| c.borrow().angle_mode);
+        let digits = Number::working_digits();
+        if _1.is_complex() {
+            let z = math::to_radians(&_1, mode, digits);
+            let sin = complex::sin(&z, digits).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })?;
+            let cos = complex::cos(&z, digits).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })?;
+            return Ok(sin.div(&cos, digits));
+        }
+        let cos = math::cos(&_1, mode, digits);
+        if cos.is_zero() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        Ok(math::sin(&_1, mode, digits).div(&cos, digits))
     }
 }
 
@@ -594,12 +1148,20 @@ impl ExpOpUnit for TanFunc {
         new_instance.set_exp_idx(self.get_exp_idx());
         Box::new(new_instance)
     }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
 }
 
 impl ExcutableUnit for TanFunc {
-    fn execute(&self) -> Result<f64, String> {
+    fn execute(&self) -> Result<Number, CalcError> {
         UnaryFunctionUnit::execute(self)
     }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
 }
 
 impl ExpUnit for TanFunc {
@@ -620,20 +1182,20 @@ impl ExpUnit for TanFunc {
     }
 }
 
-/// square function
-struct SquareFunc {
+/// natural logarithm
+struct LnFunc {
     base: UnaryFunctionBase,
 }
 
-impl SquareFunc {
+impl LnFunc {
     pub fn new() -> Self {
         Self {
-            base: UnaryFunctionBase::new(ID_SQR, PRIODITY_UNARY_OP),
+            base: UnaryFunctionBase::new(ID_LN, PRIODITY_UNARY_OP),
         }
     }    
 }
 
-impl UnaryFunctionUnit for SquareFunc {
+impl UnaryFunctionUnit for LnFunc {
     fn get_func_base(&self) -> &UnaryFunctionBase {
         &self.base
     }
@@ -642,12 +1204,23 @@ impl UnaryFunctionUnit for SquareFunc {
         &mut self.base
     }
 
-    fn execute_with_args(&self, _1: f64) -> Result<f64, String> {
-        Ok(_1 * _1)
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_zero() || (_1.is_negative() && !complex_mode()) {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        let digits = Number::working_digits();
+        if !_1.is_positive() {
+            return Ok(complex::ln(&_1, digits));
+        }
+        Ok(_1.ln(digits))
+    }
+
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        Some(_1.ln())
     }
 }
 
-impl ExpOpUnit for SquareFunc {
+impl ExpOpUnit for LnFunc {
     fn get_op_base(&self) -> &ExpOpBase {
         UnaryFunctionUnit::get_op_base(self)
     }
@@ -663,47 +1236,36 @@ impl ExpOpUnit for SquareFunc {
     fn arg_count(&self) -> i32 {
         UnaryFunctionUnit::arg_count(self)
     }
-    
+
     fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
-        let mut new_instance = SquareFunc::new();
+        let mut new_instance = LnFunc::new();
         new_instance.base._1 = self.base._1.take();
         new_instance.set_exp_idx(self.get_exp_idx());
         Box::new(new_instance)
     }
 
-    fn is_operator(&self) -> bool {
+    fn accepts_complex(&self) -> bool {
         true
     }
 }
 
-impl ExcutableUnit for SquareFunc {
-    fn execute(&self) -> Result<f64, String> {
+impl ExcutableUnit for LnFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
         UnaryFunctionUnit::execute(self)
     }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
 }
 
-impl ExpUnit for SquareFunc {
+impl ExpUnit for LnFunc {
     fn to_string(&self) -> String {
-        let base = self.get_func_base();
-        let _1 = &base._1;
-
-        match _1 {
-            None => {
-                self.exp_name().to_string()
-            },
-            Some(op_1) => {
-                if op_1.exp_name() == EXP_UNIT_NAME_CONSTANT || op_1.exp_name() == EXP_UNIT_NAME_OPEN_BRK {
-                    format!("{}{}", op_1.to_string(), self.exp_name())
-                }
-                else {
-                    format!("({}){}", op_1.to_string(), self.exp_name())
-                }
-            }
-        }
+        UnaryFunctionUnit::to_string(self)
     }
 
     fn exp_name(&self) -> &str {
-        EXP_UNIT_NAME_SQR
+        EXP_UNIT_NAME_LN
     }
 
     fn get_exp_unit_base(&self) -> &ExpUnitBase {
@@ -715,20 +1277,20 @@ impl ExpUnit for SquareFunc {
     }
 }
 
-/// square root function
-struct SqrtFunc {
+/// common logarithm
+struct Log10Func {
     base: UnaryFunctionBase,
 }
 
-impl SqrtFunc {
+impl Log10Func {
     pub fn new() -> Self {
         Self {
-            base: UnaryFunctionBase::new(ID_SQRT, PRIODITY_UNARY_OP),
+            base: UnaryFunctionBase::new(ID_LOG10, PRIODITY_UNARY_OP),
         }
     }    
 }
 
-impl UnaryFunctionUnit for SqrtFunc {
+impl UnaryFunctionUnit for Log10Func {
     fn get_func_base(&self) -> &UnaryFunctionBase {
         &self.base
     }
@@ -737,12 +1299,20 @@ impl UnaryFunctionUnit for SqrtFunc {
         &mut self.base
     }
 
-    fn execute_with_args(&self, _1: f64) -> Result<f64, String> {
-        Ok(_1.sqrt())
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_zero() || (_1.is_negative() && !complex_mode()) {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        let digits = Number::working_digits();
+        Ok(complex::ln(&_1, digits).div(&Number::from(10).ln(digits), digits))
+    }
+
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        Some(_1.log10())
     }
 }
 
-impl ExpOpUnit for SqrtFunc {
+impl ExpOpUnit for Log10Func {
     fn get_op_base(&self) -> &ExpOpBase {
         UnaryFunctionUnit::get_op_base(self)
     }
@@ -758,32 +1328,36 @@ impl ExpOpUnit for SqrtFunc {
     fn arg_count(&self) -> i32 {
         UnaryFunctionUnit::arg_count(self)
     }
-    
+
     fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
-        let mut new_instance = SqrtFunc::new();
+        let mut new_instance = Log10Func::new();
         new_instance.base._1 = self.base._1.take();
         new_instance.set_exp_idx(self.get_exp_idx());
         Box::new(new_instance)
     }
 
-    fn is_operator(&self) -> bool {
+    fn accepts_complex(&self) -> bool {
         true
     }
 }
 
-impl ExcutableUnit for SqrtFunc {
-    fn execute(&self) -> Result<f64, String> {
+impl ExcutableUnit for Log10Func {
+    fn execute(&self) -> Result<Number, CalcError> {
         UnaryFunctionUnit::execute(self)
     }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
 }
 
-impl ExpUnit for SqrtFunc {
+impl ExpUnit for Log10Func {
     fn to_string(&self) -> String {
         UnaryFunctionUnit::to_string(self)
     }
 
     fn exp_name(&self) -> &str {
-        EXP_UNIT_NAME_SQRT
+        EXP_UNIT_NAME_LOG10
     }
 
     fn get_exp_unit_base(&self) -> &ExpUnitBase {
@@ -795,20 +1369,20 @@ impl ExpUnit for SqrtFunc {
     }
 }
 
-/// inverse function
-struct InvFunc {
+/// binary logarithm
+struct Log2Func {
     base: UnaryFunctionBase,
 }
 
-impl InvFunc {
+impl Log2Func {
     pub fn new() -> Self {
         Self {
-            base: UnaryFunctionBase::new(ID_INV, PRIODITY_UNARY_OP),
+            base: UnaryFunctionBase::new(ID_LOG2, PRIODITY_UNARY_OP),
         }
     }    
 }
 
-impl UnaryFunctionUnit for InvFunc {
+impl UnaryFunctionUnit for Log2Func {
     fn get_func_base(&self) -> &UnaryFunctionBase {
         &self.base
     }
@@ -817,12 +1391,20 @@ impl UnaryFunctionUnit for InvFunc {
         &mut self.base
     }
 
-    fn execute_with_args(&self, _1: f64) -> Result<f64, String> {
-        Ok(1.0 / _1)
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_zero() || (_1.is_negative() && !complex_mode()) {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        let digits = Number::working_digits();
+        Ok(complex::ln(&_1, digits).div(&Number::from(2).ln(digits), digits))
+    }
+
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        Some(_1.log2())
     }
 }
 
-impl ExpOpUnit for InvFunc {
+impl ExpOpUnit for Log2Func {
     fn get_op_base(&self) -> &ExpOpBase {
         UnaryFunctionUnit::get_op_base(self)
     }
@@ -839,32 +1421,35 @@ impl ExpOpUnit for InvFunc {
         UnaryFunctionUnit::arg_count(self)
     }
 
-    
     fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
-        let mut new_instance = InvFunc::new();
+        let mut new_instance = Log2Func::new();
         new_instance.base._1 = self.base._1.take();
         new_instance.set_exp_idx(self.get_exp_idx());
         Box::new(new_instance)
     }
 
-    fn is_operator(&self) -> bool {
+    fn accepts_complex(&self) -> bool {
         true
     }
 }
 
-impl ExcutableUnit for InvFunc {
-    fn execute(&self) -> Result<f64, String> {
+impl ExcutableUnit for Log2Func {
+    fn execute(&self) -> Result<Number, CalcError> {
         UnaryFunctionUnit::execute(self)
     }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
 }
 
-impl ExpUnit for InvFunc {
+impl ExpUnit for Log2Func {
     fn to_string(&self) -> String {
         UnaryFunctionUnit::to_string(self)
     }
 
     fn exp_name(&self) -> &str {
-        EXP_UNIT_NAME_INV
+        EXP_UNIT_NAME_LOG2
     }
 
     fn get_exp_unit_base(&self) -> &ExpUnitBase {
@@ -876,77 +1461,178 @@ impl ExpUnit for InvFunc {
     }
 }
 
-/// add operator
-struct AddOperator {
-    base: BinaryFunctionBase,
+/// exponential function
+struct ExpFunc {
+    base: UnaryFunctionBase,
 }
 
-impl AddOperator {
+impl ExpFunc {
     pub fn new() -> Self {
         Self {
-            base: BinaryFunctionBase::new(ID_ADD, PRIODITY_ADDITIVE),
+            base: UnaryFunctionBase::new(ID_EXP, PRIODITY_UNARY_OP),
         }
     }    
 }
 
-impl BinaryFunctionUnit for AddOperator {
-    fn get_func_base(&self) -> &BinaryFunctionBase {
+impl UnaryFunctionUnit for ExpFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
         &self.base
     }
 
-    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
         &mut self.base
     }
 
-    fn execute_with_args(&self, _1: f64, _2: f64) -> Result<f64, String> {
-        Ok(_1 + _2)
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_complex() {
+            return complex::exp(&_1, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() });
+        }
+        _1.exp(Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
+    }
+
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        Some(_1.exp())
     }
 }
 
-impl ExcutableUnit for AddOperator {
-    fn execute(&self) -> Result<f64, String> {
-        BinaryFunctionUnit::execute(self)
+impl ExpOpUnit for ExpFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = ExpFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
     }
 }
 
-impl ExpOpUnit for AddOperator {
+impl ExcutableUnit for ExpFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for ExpFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_EXP
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// power of ten function
+struct Exp10Func {
+    base: UnaryFunctionBase,
+}
+
+impl Exp10Func {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_EXP10, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for Exp10Func {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        let digits = Number::working_digits();
+        if _1.is_complex() {
+            return complex::exp(&(_1 * Number::from(10).ln(digits)), digits).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() });
+        }
+        Number::from(10).pow(&_1, digits).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
+    }
+
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        Some(10f64.powf(_1))
+    }
+}
+
+impl ExpOpUnit for Exp10Func {
     fn get_op_base(&self) -> &ExpOpBase {
-        BinaryFunctionUnit::get_op_base(self)
+        UnaryFunctionUnit::get_op_base(self)
     }
 
     fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
-        BinaryFunctionUnit::get_op_base_mut(self)
+        UnaryFunctionUnit::get_op_base_mut(self)
     }
 
     fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
-        BinaryFunctionUnit::push_operand(self, operand)
+        UnaryFunctionUnit::push_operand(self, operand)
     }
 
     fn arg_count(&self) -> i32 {
-        BinaryFunctionUnit::arg_count(self)
+        UnaryFunctionUnit::arg_count(self)
     }
 
-    
     fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
-        let mut new_instance = AddOperator::new();
+        let mut new_instance = Exp10Func::new();
         new_instance.base._1 = self.base._1.take();
-        new_instance.base._2 = self.base._2.take();
         new_instance.set_exp_idx(self.get_exp_idx());
         Box::new(new_instance)
     }
 
-    fn is_operator(&self) -> bool {
+    fn accepts_complex(&self) -> bool {
         true
     }
 }
 
-impl ExpUnit for AddOperator {
+impl ExcutableUnit for Exp10Func {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for Exp10Func {
     fn to_string(&self) -> String {
-        BinaryFunctionUnit::to_string(self)
+        UnaryFunctionUnit::to_string(self)
     }
 
     fn exp_name(&self) -> &str {
-        EXP_UNIT_NAME_ADD
+        EXP_UNIT_NAME_EXP10
     }
 
     fn get_exp_unit_base(&self) -> &ExpUnitBase {
@@ -958,76 +1644,4584 @@ impl ExpUnit for AddOperator {
     }
 }
 
-/// sub operator
-struct SubOperator {
-    base: BinaryFunctionBase,
+/// absolute value function
+struct AbsFunc {
+    base: UnaryFunctionBase,
+}
+
+impl AbsFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_ABS, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for AbsFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        Ok(_1.abs())
+    }
+}
+
+impl ExpOpUnit for AbsFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = AbsFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for AbsFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for AbsFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ABS
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// real part
+struct ReFunc {
+    base: UnaryFunctionBase,
+}
+
+impl ReFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_RE, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for ReFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        Ok(_1.re())
+    }
+}
+
+impl ExpOpUnit for ReFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = ReFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for ReFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for ReFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_RE
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// imaginary part
+struct ImFunc {
+    base: UnaryFunctionBase,
+}
+
+impl ImFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_IM, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for ImFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        Ok(_1.im())
+    }
+}
+
+impl ExpOpUnit for ImFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = ImFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for ImFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for ImFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_IM
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// argument of a complex number
+struct ArgFunc {
+    base: UnaryFunctionBase,
+}
+
+impl ArgFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_ARG, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for ArgFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_zero() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        let mode = Context::with_current(|c| c.borrow().angle_mode);
+        Ok(math::atan2(&_1.im(), &_1.re(), mode, Number::working_digits()))
+    }
+}
+
+impl ExpOpUnit for ArgFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = ArgFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for ArgFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for ArgFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ARG
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// complex conjugate
+struct ConjFunc {
+    base: UnaryFunctionBase,
+}
+
+impl ConjFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_CONJ, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for ConjFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        Ok(_1.conj())
+    }
+}
+
+impl ExpOpUnit for ConjFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = ConjFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for ConjFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for ConjFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_CONJ
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// inverse sine function
+struct AsinFunc {
+    base: UnaryFunctionBase,
+}
+
+impl AsinFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_ASIN, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for AsinFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        let mode = Context::with_current(|c| c.borrow().angle_mode);
+        let digits = Number::working_digits();
+        if _1.is_complex() || _1.abs() > Number::one() {
+            if !_1.is_complex() && !complex_mode() {
+                return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+            }
+            return Ok(math::from_radians(&complex::asin(&_1, digits), mode, digits));
+        }
+        Ok(math::asin(&_1, mode, digits))
+    }
+}
+
+impl ExpOpUnit for AsinFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = AsinFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for AsinFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for AsinFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ASIN
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// inverse cosine function
+struct AcosFunc {
+    base: UnaryFunctionBase,
+}
+
+impl AcosFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_ACOS, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for AcosFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        let mode = Context::with_current(|c| c.borrow().angle_mode);
+        let digits = Number::working_digits();
+        if _1.is_complex() || _1.abs() > Number::one() {
+            if !_1.is_complex() && !complex_mode() {
+                return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+            }
+            return Ok(math::from_radians(&complex::acos(&_1, digits), mode, digits));
+        }
+        Ok(math::acos(&_1, mode, digits))
+    }
+}
+
+impl ExpOpUnit for AcosFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = AcosFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for AcosFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for AcosFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ACOS
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// inverse tangent function
+struct AtanFunc {
+    base: UnaryFunctionBase,
+}
+
+impl AtanFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_ATAN, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for AtanFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        let mode = Context::with_current(|c| c.borrow().angle_mode);
+        let digits = Number::working_digits();
+        if _1.is_complex() {
+            // the poles of atan are ±i
+            if _1 == Number::i() || _1 == -Number::i() {
+                return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+            }
+            return Ok(math::from_radians(&complex::atan(&_1, digits), mode, digits));
+        }
+        Ok(math::atan(&_1, mode, digits))
+    }
+}
+
+impl ExpOpUnit for AtanFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = AtanFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for AtanFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for AtanFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ATAN
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// hyperbolic sine function
+struct SinhFunc {
+    base: UnaryFunctionBase,
+}
+
+impl SinhFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_SINH, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for SinhFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_complex() {
+            return complex::sinh(&_1, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() });
+        }
+        math::sinh(&_1, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
+    }
+
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        Some(_1.sinh())
+    }
+}
+
+impl ExpOpUnit for SinhFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = SinhFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for SinhFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for SinhFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_SINH
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// hyperbolic cosine function
+struct CoshFunc {
+    base: UnaryFunctionBase,
+}
+
+impl CoshFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_COSH, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for CoshFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_complex() {
+            return complex::cosh(&_1, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() });
+        }
+        math::cosh(&_1, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
+    }
+
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        Some(_1.cosh())
+    }
+}
+
+impl ExpOpUnit for CoshFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = CoshFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for CoshFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for CoshFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_COSH
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// hyperbolic tangent function
+struct TanhFunc {
+    base: UnaryFunctionBase,
+}
+
+impl TanhFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_TANH, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for TanhFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        let digits = Number::working_digits();
+        if _1.is_complex() {
+            let cosh = complex::cosh(&_1, digits).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })?;
+            return Ok(complex::sinh(&_1, digits).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })?.div(&cosh, digits));
+        }
+        Ok(math::tanh(&_1, digits))
+    }
+
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        Some(_1.tanh())
+    }
+}
+
+impl ExpOpUnit for TanhFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = TanhFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for TanhFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for TanhFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_TANH
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// inverse hyperbolic sine function
+struct AsinhFunc {
+    base: UnaryFunctionBase,
+}
+
+impl AsinhFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_ASINH, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for AsinhFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_complex() {
+            return Ok(complex::asinh(&_1, Number::working_digits()));
+        }
+        Ok(math::asinh(&_1, Number::working_digits()))
+    }
+}
+
+impl ExpOpUnit for AsinhFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = AsinhFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for AsinhFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for AsinhFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ASINH
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// inverse hyperbolic cosine function
+struct AcoshFunc {
+    base: UnaryFunctionBase,
+}
+
+impl AcoshFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_ACOSH, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for AcoshFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_complex() || _1 < Number::one() {
+            if !_1.is_complex() && !complex_mode() {
+                return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+            }
+            return Ok(complex::acosh(&_1, Number::working_digits()));
+        }
+        Ok(math::acosh(&_1, Number::working_digits()))
+    }
+}
+
+impl ExpOpUnit for AcoshFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = AcoshFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for AcoshFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for AcoshFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ACOSH
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// inverse hyperbolic tangent function
+struct AtanhFunc {
+    base: UnaryFunctionBase,
+}
+
+impl AtanhFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_ATANH, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for AtanhFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_complex() || _1.abs() > Number::one() {
+            if !_1.is_complex() && !complex_mode() {
+                return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+            }
+            return Ok(complex::atanh(&_1, Number::working_digits()));
+        }
+        if _1.abs() == Number::one() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        Ok(_1.atanh(Number::working_digits()))
+    }
+}
+
+impl ExpOpUnit for AtanhFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = AtanhFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for AtanhFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for AtanhFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ATANH
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// square function
+struct SquareFunc {
+    base: UnaryFunctionBase,
+}
+
+impl SquareFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_SQR, PRIODITY_POSTFIX_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for SquareFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        Ok(&_1 * &_1)
+    }
+
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        Some(_1 * _1)
+    }
+}
+
+impl ExpOpUnit for SquareFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+    
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = SquareFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn is_postfix(&self) -> bool {
+        true
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for SquareFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for SquareFunc {
+    fn to_string(&self) -> String {
+        let base = self.get_func_base();
+        let _1 = &base._1;
+
+        match _1 {
+            None => {
+                self.exp_name().to_string()
+            },
+            Some(op_1) => {
+                if op_1.is_leaf() || op_1.exp_name() == EXP_UNIT_NAME_OPEN_BRK {
+                    format!("{}{}", op_1.to_string(), self.exp_name())
+                }
+                else {
+                    format!("({}){}", op_1.to_string(), self.exp_name())
+                }
+            }
+        }
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_SQR
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// factorial function, written after its operand
+struct FactorialFunc {
+    base: UnaryFunctionBase,
+}
+
+impl FactorialFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_FACTORIAL, PRIODITY_POSTFIX_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for FactorialFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_negative() && _1.is_integer() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        let digits = Number::working_digits();
+        // non-integers are extended by the gamma function
+        let v = if _1.is_integer() { math::factorial(&_1, digits) } else { math::gamma(&(_1 + Number::one()), digits) };
+        v.ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExpOpUnit for FactorialFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = FactorialFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn is_postfix(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for FactorialFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for FactorialFunc {
+    fn to_string(&self) -> String {
+        let base = self.get_func_base();
+        let _1 = &base._1;
+
+        match _1 {
+            None => {
+                self.exp_name().to_string()
+            },
+            Some(op_1) => {
+                if op_1.is_leaf() || op_1.exp_name() == EXP_UNIT_NAME_OPEN_BRK {
+                    format!("{}{}", op_1.to_string(), self.exp_name())
+                }
+                else {
+                    format!("({}){}", op_1.to_string(), self.exp_name())
+                }
+            }
+        }
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_FACTORIAL
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// double factorial function, written after its operand
+struct DoubleFactorialFunc {
+    base: UnaryFunctionBase,
+}
+
+impl DoubleFactorialFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_DOUBLE_FACTORIAL, PRIODITY_POSTFIX_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for DoubleFactorialFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1 < -Number::one() || !_1.is_integer() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        math::double_factorial(&_1, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExpOpUnit for DoubleFactorialFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = DoubleFactorialFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn is_postfix(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for DoubleFactorialFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for DoubleFactorialFunc {
+    fn to_string(&self) -> String {
+        let base = self.get_func_base();
+        let _1 = &base._1;
+
+        match _1 {
+            None => {
+                self.exp_name().to_string()
+            },
+            Some(op_1) => {
+                if op_1.is_leaf() || op_1.exp_name() == EXP_UNIT_NAME_OPEN_BRK {
+                    format!("{}{}", op_1.to_string(), self.exp_name())
+                }
+                else {
+                    format!("({}){}", op_1.to_string(), self.exp_name())
+                }
+            }
+        }
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_DOUBLE_FACTORIAL
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// gamma function
+struct GammaFunc {
+    base: UnaryFunctionBase,
+}
+
+impl GammaFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_GAMMA, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for GammaFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if !_1.is_positive() && _1.is_integer() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        math::gamma(&_1, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExpOpUnit for GammaFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = GammaFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+}
+
+impl ExcutableUnit for GammaFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for GammaFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_GAMMA
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// square root function
+struct SqrtFunc {
+    base: UnaryFunctionBase,
+}
+
+impl SqrtFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_SQRT, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for SqrtFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_negative() && !complex_mode() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        if _1.is_complex() || _1.is_negative() {
+            return Ok(complex::sqrt(&_1, Number::working_digits()));
+        }
+        Ok(_1.sqrt(Number::working_digits()))
+    }
+
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        Some(_1.sqrt())
+    }
+}
+
+impl ExpOpUnit for SqrtFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+    
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = SqrtFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for SqrtFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for SqrtFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_SQRT
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// inverse function
+struct InvFunc {
+    base: UnaryFunctionBase,
+}
+
+impl InvFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_INV, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for InvFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        if _1.is_zero() {
+            return Err(CalcError::DivisionByZero { exp_idx: self.get_exp_idx() });
+        }
+        Ok(Number::one().div(&_1, Number::working_digits()))
+    }
+
+    fn execute_f64(&self, _1: f64) -> Option<f64> {
+        Some(1.0 / _1)
+    }
+}
+
+impl ExpOpUnit for InvFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = InvFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for InvFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for InvFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_INV
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// negation, written as a minus sign in front of its operand
+struct NegFunc {
+    base: UnaryFunctionBase,
+}
+
+impl NegFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_NEG, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for NegFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        Ok(-_1)
+    }
+}
+
+impl ExpOpUnit for NegFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = NegFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for NegFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for NegFunc {
+    fn to_string(&self) -> String {
+        let base = self.get_func_base();
+        match &base._1 {
+            None => {
+                EXP_UNIT_NAME_SUB.to_string()
+            },
+            Some(op_1) => {
+                format!("{}{}", EXP_UNIT_NAME_SUB, op_1.to_string())
+            }
+        }
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_NEG
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// add operator
+struct AddOperator {
+    base: BinaryFunctionBase,
+}
+
+impl AddOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_ADD, PRIODITY_ADDITIVE),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for AddOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        Ok(_1 + _2)
+    }
+}
+
+impl ExcutableUnit for AddOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for AddOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = AddOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for AddOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ADD
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// sub operator
+struct SubOperator {
+    base: BinaryFunctionBase,
+}
+
+impl SubOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_SUB, PRIODITY_ADDITIVE),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for SubOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        Ok(_1 - _2)
+    }
+}
+
+impl ExcutableUnit for SubOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for SubOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = SubOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for SubOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_SUB
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// mul operator
+struct MulOperator {
+    base: BinaryFunctionBase,
+}
+
+impl MulOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_MUL, PRIODITY_MULTIPLICATIVE),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for MulOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        Ok(_1 * _2)
+    }
+}
+
+impl ExcutableUnit for MulOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for MulOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = MulOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for MulOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_MUL
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// mul operator inserted between juxtaposed operands, e.g. 2π or (a)(b)
+struct ImplicitMulOperator {
+    base: BinaryFunctionBase,
+}
+
+impl ImplicitMulOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_IMPLICIT_MUL, PRIODITY_IMPLICIT_MUL),
+        }
+    }    
+
+    // implicit multiplication with the precedence chosen in the context
+    fn create() -> Box<dyn ExpOpUnit> {
+        let mut op: Box<dyn ExpOpUnit> = Box::new(ImplicitMulOperator::new());
+        if Context::with_current(|c| c.borrow().implicit_mul_precedence) == ImplicitMulPrecedence::Normal {
+            op.get_op_base_mut().precedence = PRIODITY_MULTIPLICATIVE;
+        }
+        op
+    }
+}
+
+impl BinaryFunctionUnit for ImplicitMulOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        Ok(_1 * _2)
+    }
+}
+
+impl ExcutableUnit for ImplicitMulOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for ImplicitMulOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = ImplicitMulOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for ImplicitMulOperator {
+    fn to_string(&self) -> String {
+        let base = self.get_func_base();
+
+        // shown the way it was written, without the multiplication sign
+        match (&base._1, &base._2) {
+            (Some(op_1), Some(op_2)) => juxtapose(&op_1.to_string(), &op_2.to_string()),
+            (Some(op_1), None) => op_1.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_MUL
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// div operator
+struct DivOperator {
+    base: BinaryFunctionBase,
+}
+
+impl DivOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_DIV, PRIODITY_MULTIPLICATIVE),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for DivOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        if _2.is_zero() {
+            return Err(CalcError::DivisionByZero { exp_idx: self.get_exp_idx() });
+        }
+        Ok(_1.div(&_2, Number::working_digits()))
+    }
+
+    fn execute_f64(&self, _1: f64, _2: f64) -> Option<f64> {
+        Some(_1 / _2)
+    }
+}
+
+impl ExcutableUnit for DivOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for DivOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+    
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = DivOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for DivOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_DIV
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+
+/// modulo operator, the quotient is floored so the result has the sign of the divisor: -7 mod 3 = 2
+struct ModOperator {
+    base: BinaryFunctionBase,
+}
+
+impl ModOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_MOD, PRIODITY_MULTIPLICATIVE),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for ModOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        if _2.is_zero() {
+            return Err(CalcError::DivisionByZero { exp_idx: self.get_exp_idx() });
+        }
+        let r = _1.rem(&_2);
+        if !r.is_zero() && r.is_negative() != _2.is_negative() {
+            Ok(r + _2)
+        }
+        else {
+            Ok(r)
+        }
+    }
+}
+
+impl ExcutableUnit for ModOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for ModOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = ModOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for ModOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_MOD
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// integer division operator, the quotient is floored: -7 div 3 = -3, so x = y×(x div y) + x mod y
+struct IntDivOperator {
+    base: BinaryFunctionBase,
+}
+
+impl IntDivOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_INT_DIV, PRIODITY_MULTIPLICATIVE),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for IntDivOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        if _2.is_zero() {
+            return Err(CalcError::DivisionByZero { exp_idx: self.get_exp_idx() });
+        }
+        Ok(_1.div_floor(&_2))
+    }
+}
+
+impl ExcutableUnit for IntDivOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for IntDivOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = IntDivOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for IntDivOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_INT_DIV
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// remainder operator, the quotient is truncated so the result has the sign of the dividend: -7 rem 3 = -1
+struct RemOperator {
+    base: BinaryFunctionBase,
+}
+
+impl RemOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_REM, PRIODITY_MULTIPLICATIVE),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for RemOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        if _2.is_zero() {
+            return Err(CalcError::DivisionByZero { exp_idx: self.get_exp_idx() });
+        }
+        Ok(_1.rem(&_2))
+    }
+
+    fn execute_f64(&self, _1: f64, _2: f64) -> Option<f64> {
+        Some(_1 % _2)
+    }
+}
+
+impl ExcutableUnit for RemOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for RemOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = RemOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for RemOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_REM
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+// bits of a shift, a longer shift gives the same result as one to the end of the word or to the overflow
+fn shift_count(n: &Number, exp_idx: i32) -> Result<i64, CalcError> {
+    let n = n.to_i64().filter(|n| *n >= 0).ok_or(CalcError::Domain { exp_idx })?;
+    let limit = Context::with_current(|c| c.borrow().integer_mode).map_or(4 * MAX_EXPONENT, |mode| mode.bits as i64);
+    Ok(n.min(limit))
+}
+
+// bits of the word of the programmer mode, rotations have no meaning out of it
+fn word_size(exp_idx: i32) -> Result<u32, CalcError> {
+    Context::with_current(|c| c.borrow().integer_mode).map(|mode| mode.bits).ok_or(CalcError::Domain { exp_idx })
+}
+
+/// bitwise and operator, negative integers are taken in two's complement: 12 AND 10 = 8
+struct AndOperator {
+    base: BinaryFunctionBase,
+}
+
+impl AndOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_AND, PRIODITY_BITWISE_AND),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for AndOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        _1.bit_and(&_2).ok_or(CalcError::Domain { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExcutableUnit for AndOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for AndOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = AndOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for AndOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_AND
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// bitwise or operator: 12 OR 10 = 14
+struct OrOperator {
+    base: BinaryFunctionBase,
+}
+
+impl OrOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_OR, PRIODITY_BITWISE_OR),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for OrOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        _1.bit_or(&_2).ok_or(CalcError::Domain { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExcutableUnit for OrOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for OrOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = OrOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for OrOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_OR
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// bitwise exclusive or operator: 12 XOR 10 = 6
+struct XorOperator {
+    base: BinaryFunctionBase,
+}
+
+impl XorOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_XOR, PRIODITY_BITWISE_XOR),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for XorOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        _1.bit_xor(&_2).ok_or(CalcError::Domain { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExcutableUnit for XorOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for XorOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = XorOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for XorOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_XOR
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// bitwise complement function, NOT x = -x-1, in an unsigned word every bit is flipped
+struct NotFunc {
+    base: UnaryFunctionBase,
+}
+
+impl NotFunc {
+    pub fn new() -> Self {
+        Self {
+            base: UnaryFunctionBase::new(ID_NOT, PRIODITY_UNARY_OP),
+        }
+    }    
+}
+
+impl UnaryFunctionUnit for NotFunc {
+    fn get_func_base(&self) -> &UnaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut UnaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number) -> Result<Number, CalcError> {
+        _1.bit_not().ok_or(CalcError::Domain { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExpOpUnit for NotFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        UnaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        UnaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        UnaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        UnaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = NotFunc::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+}
+
+impl ExcutableUnit for NotFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        UnaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        UnaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for NotFunc {
+    fn to_string(&self) -> String {
+        UnaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_NOT
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// left shift operator: 1 << 4 = 16
+struct ShlOperator {
+    base: BinaryFunctionBase,
+}
+
+impl ShlOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_SHL, PRIODITY_SHIFT),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for ShlOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        let n = shift_count(&_2, self.get_exp_idx())?;
+        _1.shift(n).ok_or(CalcError::Domain { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExcutableUnit for ShlOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for ShlOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = ShlOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for ShlOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_SHL
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// right shift operator, it keeps the sign: -16 >> 2 = -4
+struct ShrOperator {
+    base: BinaryFunctionBase,
+}
+
+impl ShrOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_SHR, PRIODITY_SHIFT),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for ShrOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        let n = shift_count(&_2, self.get_exp_idx())?;
+        _1.shift(-n).ok_or(CalcError::Domain { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExcutableUnit for ShrOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for ShrOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = ShrOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for ShrOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_SHR
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// rotate left operator, the bits shifted out of the word of the programmer mode come back in on the right
+struct RolOperator {
+    base: BinaryFunctionBase,
+}
+
+impl RolOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_ROL, PRIODITY_SHIFT),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for RolOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        let bits = word_size(self.get_exp_idx())?;
+        let n = _2.to_i64().ok_or(CalcError::Domain { exp_idx: self.get_exp_idx() })?;
+        _1.rotate(n, bits).ok_or(CalcError::Domain { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExcutableUnit for RolOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for RolOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = RolOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for RolOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ROL
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// rotate right operator
+struct RorOperator {
+    base: BinaryFunctionBase,
+}
+
+impl RorOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_ROR, PRIODITY_SHIFT),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for RorOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        let bits = word_size(self.get_exp_idx())?;
+        let n = _2.to_i64().ok_or(CalcError::Domain { exp_idx: self.get_exp_idx() })?;
+        _1.rotate(n.checked_neg().ok_or(CalcError::Domain { exp_idx: self.get_exp_idx() })?, bits).ok_or(CalcError::Domain { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExcutableUnit for RorOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for RorOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = RorOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for RorOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ROR
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// logarithm function, logb(x, b) is the logarithm of x in base b
+struct LogbFunc {
+    base: VariadicFunctionBase,
+}
+
+impl LogbFunc {
+    pub fn new() -> Self {
+        Self {
+            base: VariadicFunctionBase::new(ID_LOGB, PRIODITY_UNARY_OP, 2, 2),
+        }
+    }    
+}
+
+impl VariadicFunctionUnit for LogbFunc {
+    fn get_func_base(&self) -> &VariadicFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut VariadicFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, args: &[Number]) -> Result<Number, CalcError> {
+        let (x, b) = (&args[0], &args[1]);
+        if x.is_zero() || b.is_zero() || *b == Number::one() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        if (x.is_negative() || b.is_negative()) && !complex_mode() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        let digits = Number::working_digits();
+        Ok(complex::ln(x, digits).div(&complex::ln(b, digits), digits))
+    }
+}
+
+impl ExpOpUnit for LogbFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        VariadicFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        VariadicFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        VariadicFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        VariadicFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = LogbFunc::new();
+        new_instance.base.args = std::mem::take(&mut self.base.args);
+        new_instance.base.arg_count = self.base.arg_count;
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn takes_argument_list(&self) -> bool {
+        true
+    }
+
+    fn arg_count_range(&self) -> (i32, i32) {
+        VariadicFunctionUnit::arg_count_range(self)
+    }
+
+    fn set_arg_count(&mut self, count: i32) {
+        VariadicFunctionUnit::set_arg_count(self, count)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for LogbFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        VariadicFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        VariadicFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for LogbFunc {
+    fn to_string(&self) -> String {
+        VariadicFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_LOGB
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// two-argument inverse tangent function, atan2(y, x) is the angle of the point (x, y)
+struct Atan2Func {
+    base: VariadicFunctionBase,
+}
+
+impl Atan2Func {
+    pub fn new() -> Self {
+        Self {
+            base: VariadicFunctionBase::new(ID_ATAN2, PRIODITY_UNARY_OP, 2, 2),
+        }
+    }    
+}
+
+impl VariadicFunctionUnit for Atan2Func {
+    fn get_func_base(&self) -> &VariadicFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut VariadicFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, args: &[Number]) -> Result<Number, CalcError> {
+        let (y, x) = (&args[0], &args[1]);
+        if y.is_zero() && x.is_zero() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        let mode = Context::with_current(|c| c.borrow().angle_mode);
+        Ok(math::atan2(y, x, mode, Number::working_digits()))
+    }
+}
+
+impl ExpOpUnit for Atan2Func {
+    fn get_op_base(&self) -> &ExpOpBase {
+        VariadicFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        VariadicFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        VariadicFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        VariadicFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = Atan2Func::new();
+        new_instance.base.args = std::mem::take(&mut self.base.args);
+        new_instance.base.arg_count = self.base.arg_count;
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn takes_argument_list(&self) -> bool {
+        true
+    }
+
+    fn arg_count_range(&self) -> (i32, i32) {
+        VariadicFunctionUnit::arg_count_range(self)
+    }
+
+    fn set_arg_count(&mut self, count: i32) {
+        VariadicFunctionUnit::set_arg_count(self, count)
+    }
+}
+
+impl ExcutableUnit for Atan2Func {
+    fn execute(&self) -> Result<Number, CalcError> {
+        VariadicFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        VariadicFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for Atan2Func {
+    fn to_string(&self) -> String {
+        VariadicFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ATAN2
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// permutations operator, n nPr r is the number of ordered selections of r items out of n
+struct PermutationsOperator {
+    base: BinaryFunctionBase,
+}
+
+impl PermutationsOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_PERMUTATIONS, PRIODITY_COMBINATORIAL),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for PermutationsOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        if !_1.is_integer() || !_2.is_integer() || _1.is_negative() || _2.is_negative() || _2 > _1 {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        math::permutations(&_1, &_2, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExcutableUnit for PermutationsOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for PermutationsOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = PermutationsOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for PermutationsOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_PERMUTATIONS
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// combinations operator, n nCr r is the number of unordered selections of r items out of n
+struct CombinationsOperator {
+    base: BinaryFunctionBase,
+}
+
+impl CombinationsOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_COMBINATIONS, PRIODITY_COMBINATORIAL),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for CombinationsOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        if !_1.is_integer() || !_2.is_integer() || _1.is_negative() || _2.is_negative() || _2 > _1 {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        math::combinations(&_1, &_2, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExcutableUnit for CombinationsOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for CombinationsOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = CombinationsOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for CombinationsOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_COMBINATIONS
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// power operator, x^y
+struct PowOperator {
+    base: BinaryFunctionBase,
+}
+
+impl PowOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_POW, PRIODITY_POWER),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for PowOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        if _1.is_complex() || _2.is_complex() || (_1.is_negative() && !_2.is_integer() && complex_mode()) {
+            // 0^w is 0 when the real part of w is positive
+            if _1.is_zero() {
+                return if _2.re().is_positive() { Ok(Number::zero()) } else { Err(CalcError::Domain { exp_idx: self.get_exp_idx() }) };
+            }
+            return complex::pow(&_1, &_2, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() });
+        }
+        if _1.is_negative() && !_2.is_integer() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        if _1.is_zero() && _2.is_negative() {
+            return Err(CalcError::DivisionByZero { exp_idx: self.get_exp_idx() });
+        }
+        _1.pow(&_2, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
+    }
+
+    fn execute_f64(&self, _1: f64, _2: f64) -> Option<f64> {
+        Some(_1.powf(_2))
+    }
+}
+
+impl ExcutableUnit for PowOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for PowOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = PowOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn is_right_associative(&self) -> bool {
+        true
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for PowOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_POW
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// nth root operator, xʸ√y is the y-th root of x
+struct RootOperator {
+    base: BinaryFunctionBase,
+}
+
+impl RootOperator {
+    pub fn new() -> Self {
+        Self {
+            base: BinaryFunctionBase::new(ID_ROOT, PRIODITY_POWER),
+        }
+    }    
+}
+
+impl BinaryFunctionUnit for RootOperator {
+    fn get_func_base(&self) -> &BinaryFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, _1: Number, _2: Number) -> Result<Number, CalcError> {
+        if _2.is_zero() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        if _1.is_zero() && _2.is_negative() {
+            return Err(CalcError::DivisionByZero { exp_idx: self.get_exp_idx() });
+        }
+        // only odd roots of a negative number are real, the others are complex
+        let real_root = !_1.is_negative() || (_2.is_integer() && !_2.rem(&Number::from(2)).is_zero());
+        if _1.is_complex() || _2.is_complex() || !real_root {
+            if !_1.is_complex() && !_2.is_complex() && !complex_mode() {
+                return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+            }
+            if _1.is_zero() {
+                return Ok(Number::zero());
+            }
+            let digits = Number::working_digits();
+            return complex::pow(&_1, &Number::one().div(&_2, digits), digits).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() });
+        }
+        let precision = Context::with_current(|c| c.borrow().precision);
+        math::root(&_1, &_2, precision, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExcutableUnit for RootOperator {
+    fn execute(&self) -> Result<Number, CalcError> {
+        BinaryFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        BinaryFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpOpUnit for RootOperator {
+    fn get_op_base(&self) -> &ExpOpBase {
+        BinaryFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        BinaryFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        BinaryFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        BinaryFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = RootOperator::new();
+        new_instance.base._1 = self.base._1.take();
+        new_instance.base._2 = self.base._2.take();
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn is_operator(&self) -> bool {
+        true
+    }
+
+    fn is_right_associative(&self) -> bool {
+        true
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+}
+
+impl ExpUnit for RootOperator {
+    fn to_string(&self) -> String {
+        BinaryFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_ROOT
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// min function
+struct MinFunc {
+    base: VariadicFunctionBase,
+}
+
+impl MinFunc {
+    pub fn new() -> Self {
+        Self {
+            base: VariadicFunctionBase::new(ID_MIN, PRIODITY_UNARY_OP, 1, i32::MAX),
+        }
+    }    
+}
+
+impl VariadicFunctionUnit for MinFunc {
+    fn get_func_base(&self) -> &VariadicFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut VariadicFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, args: &[Number]) -> Result<Number, CalcError> {
+        args.iter().min().cloned().ok_or(CalcError::MissingOperand { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExpOpUnit for MinFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        VariadicFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        VariadicFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        VariadicFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        VariadicFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = MinFunc::new();
+        new_instance.base.args = std::mem::take(&mut self.base.args);
+        new_instance.base.arg_count = self.base.arg_count;
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn takes_argument_list(&self) -> bool {
+        true
+    }
+
+    fn arg_count_range(&self) -> (i32, i32) {
+        VariadicFunctionUnit::arg_count_range(self)
+    }
+
+    fn set_arg_count(&mut self, count: i32) {
+        VariadicFunctionUnit::set_arg_count(self, count)
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for MinFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        VariadicFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        VariadicFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for MinFunc {
+    fn to_string(&self) -> String {
+        VariadicFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_MIN
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// max function
+struct MaxFunc {
+    base: VariadicFunctionBase,
+}
+
+impl MaxFunc {
+    pub fn new() -> Self {
+        Self {
+            base: VariadicFunctionBase::new(ID_MAX, PRIODITY_UNARY_OP, 1, i32::MAX),
+        }
+    }    
+}
+
+impl VariadicFunctionUnit for MaxFunc {
+    fn get_func_base(&self) -> &VariadicFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut VariadicFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, args: &[Number]) -> Result<Number, CalcError> {
+        args.iter().max().cloned().ok_or(CalcError::MissingOperand { exp_idx: self.get_exp_idx() })
+    }
+}
+
+impl ExpOpUnit for MaxFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        VariadicFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        VariadicFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        VariadicFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        VariadicFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = MaxFunc::new();
+        new_instance.base.args = std::mem::take(&mut self.base.args);
+        new_instance.base.arg_count = self.base.arg_count;
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn takes_argument_list(&self) -> bool {
+        true
+    }
+
+    fn arg_count_range(&self) -> (i32, i32) {
+        VariadicFunctionUnit::arg_count_range(self)
+    }
+
+    fn set_arg_count(&mut self, count: i32) {
+        VariadicFunctionUnit::set_arg_count(self, count)
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for MaxFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        VariadicFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        VariadicFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for MaxFunc {
+    fn to_string(&self) -> String {
+        VariadicFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_MAX
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// hypot function, length of the vector of its arguments
+struct HypotFunc {
+    base: VariadicFunctionBase,
+}
+
+impl HypotFunc {
+    pub fn new() -> Self {
+        Self {
+            base: VariadicFunctionBase::new(ID_HYPOT, PRIODITY_UNARY_OP, 1, i32::MAX),
+        }
+    }    
+}
+
+impl VariadicFunctionUnit for HypotFunc {
+    fn get_func_base(&self) -> &VariadicFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut VariadicFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, args: &[Number]) -> Result<Number, CalcError> {
+        let v = args.iter().fold(Number::zero(), |v, x| v + x * x);
+        Ok(v.sqrt(Number::working_digits()))
+    }
+}
+
+impl ExpOpUnit for HypotFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        VariadicFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        VariadicFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        VariadicFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        VariadicFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = HypotFunc::new();
+        new_instance.base.args = std::mem::take(&mut self.base.args);
+        new_instance.base.arg_count = self.base.arg_count;
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn takes_argument_list(&self) -> bool {
+        true
+    }
+
+    fn arg_count_range(&self) -> (i32, i32) {
+        VariadicFunctionUnit::arg_count_range(self)
+    }
+
+    fn set_arg_count(&mut self, count: i32) {
+        VariadicFunctionUnit::set_arg_count(self, count)
+    }
+}
+
+impl ExcutableUnit for HypotFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        VariadicFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        VariadicFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for HypotFunc {
+    fn to_string(&self) -> String {
+        VariadicFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_HYPOT
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// gcd function, greatest common divisor of integers
+struct GcdFunc {
+    base: VariadicFunctionBase,
+}
+
+impl GcdFunc {
+    pub fn new() -> Self {
+        Self {
+            base: VariadicFunctionBase::new(ID_GCD, PRIODITY_UNARY_OP, 1, i32::MAX),
+        }
+    }    
+}
+
+impl VariadicFunctionUnit for GcdFunc {
+    fn get_func_base(&self) -> &VariadicFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut VariadicFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, args: &[Number]) -> Result<Number, CalcError> {
+        if args.iter().any(|x| !x.is_integer()) {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        Ok(args.iter().fold(Number::zero(), |v, x| math::gcd(&v, x)))
+    }
+}
+
+impl ExpOpUnit for GcdFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        VariadicFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        VariadicFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        VariadicFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        VariadicFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = GcdFunc::new();
+        new_instance.base.args = std::mem::take(&mut self.base.args);
+        new_instance.base.arg_count = self.base.arg_count;
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn takes_argument_list(&self) -> bool {
+        true
+    }
+
+    fn arg_count_range(&self) -> (i32, i32) {
+        VariadicFunctionUnit::arg_count_range(self)
+    }
+
+    fn set_arg_count(&mut self, count: i32) {
+        VariadicFunctionUnit::set_arg_count(self, count)
+    }
+}
+
+impl ExcutableUnit for GcdFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        VariadicFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        VariadicFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for GcdFunc {
+    fn to_string(&self) -> String {
+        VariadicFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_GCD
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// sum function
+struct SumFunc {
+    base: VariadicFunctionBase,
+}
+
+impl SumFunc {
+    pub fn new() -> Self {
+        Self {
+            base: VariadicFunctionBase::new(ID_SUM, PRIODITY_UNARY_OP, 1, i32::MAX),
+        }
+    }    
+}
+
+impl VariadicFunctionUnit for SumFunc {
+    fn get_func_base(&self) -> &VariadicFunctionBase {
+        &self.base
+    }
+
+    fn get_func_base_mut(&mut self) -> &mut VariadicFunctionBase {
+        &mut self.base
+    }
+
+    fn execute_with_args(&self, args: &[Number]) -> Result<Number, CalcError> {
+        Ok(args.iter().fold(Number::zero(), |v, x| v + x))
+    }
+}
+
+impl ExpOpUnit for SumFunc {
+    fn get_op_base(&self) -> &ExpOpBase {
+        VariadicFunctionUnit::get_op_base(self)
+    }
+
+    fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
+        VariadicFunctionUnit::get_op_base_mut(self)
+    }
+
+    fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
+        VariadicFunctionUnit::push_operand(self, operand)
+    }
+
+    fn arg_count(&self) -> i32 {
+        VariadicFunctionUnit::arg_count(self)
+    }
+
+    fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
+        let mut new_instance = SumFunc::new();
+        new_instance.base.args = std::mem::take(&mut self.base.args);
+        new_instance.base.arg_count = self.base.arg_count;
+        new_instance.set_exp_idx(self.get_exp_idx());
+        Box::new(new_instance)
+    }
+
+    fn takes_argument_list(&self) -> bool {
+        true
+    }
+
+    fn arg_count_range(&self) -> (i32, i32) {
+        VariadicFunctionUnit::arg_count_range(self)
+    }
+
+    fn set_arg_count(&mut self, count: i32) {
+        VariadicFunctionUnit::set_arg_count(self, count)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
+        true
+    }
+}
+
+impl ExcutableUnit for SumFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        VariadicFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        VariadicFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for SumFunc {
+    fn to_string(&self) -> String {
+        VariadicFunctionUnit::to_string(self)
+    }
+
+    fn exp_name(&self) -> &str {
+        EXP_UNIT_NAME_SUM
+    }
+
+    fn get_exp_unit_base(&self) -> &ExpUnitBase {
+        &self.base.unitbase.unitbase
+    }
+
+    fn get_exp_unit_base_mut(&mut self) -> &mut ExpUnitBase {
+        &mut self.base.unitbase.unitbase
+    }
+}
+
+/// multinomial coefficient function
+struct MultinomialFunc {
+    base: VariadicFunctionBase,
 }
 
-impl SubOperator {
+impl MultinomialFunc {
     pub fn new() -> Self {
         Self {
-            base: BinaryFunctionBase::new(ID_SUB, PRIODITY_ADDITIVE),
+            base: VariadicFunctionBase::new(ID_MULTINOMIAL, PRIODITY_UNARY_OP, 1, i32::MAX),
         }
     }    
 }
 
-impl BinaryFunctionUnit for SubOperator {
-    fn get_func_base(&self) -> &BinaryFunctionBase {
+impl VariadicFunctionUnit for MultinomialFunc {
+    fn get_func_base(&self) -> &VariadicFunctionBase {
         &self.base
     }
 
-    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+    fn get_func_base_mut(&mut self) -> &mut VariadicFunctionBase {
         &mut self.base
     }
 
-    fn execute_with_args(&self, _1: f64, _2: f64) -> Result<f64, String> {
-        Ok(_1 - _2)
-    }
-}
-
-impl ExcutableUnit for SubOperator {
-    fn execute(&self) -> Result<f64, String> {
-        BinaryFunctionUnit::execute(self)
+    fn execute_with_args(&self, args: &[Number]) -> Result<Number, CalcError> {
+        if args.iter().any(|x| !x.is_integer() || x.is_negative()) {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+        math::multinomial(args, Number::working_digits()).ok_or(CalcError::Overflow { exp_idx: self.get_exp_idx() })
     }
 }
 
-impl ExpOpUnit for SubOperator {
+impl ExpOpUnit for MultinomialFunc {
     fn get_op_base(&self) -> &ExpOpBase {
-        BinaryFunctionUnit::get_op_base(self)
+        VariadicFunctionUnit::get_op_base(self)
     }
 
     fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
-        BinaryFunctionUnit::get_op_base_mut(self)
+        VariadicFunctionUnit::get_op_base_mut(self)
     }
 
     fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
-        BinaryFunctionUnit::push_operand(self, operand)
+        VariadicFunctionUnit::push_operand(self, operand)
     }
 
     fn arg_count(&self) -> i32 {
-        BinaryFunctionUnit::arg_count(self)
+        VariadicFunctionUnit::arg_count(self)
     }
 
-    
     fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
-        let mut new_instance = SubOperator::new();
-        new_instance.base._1 = self.base._1.take();
-        new_instance.base._2 = self.base._2.take();
+        let mut new_instance = MultinomialFunc::new();
+        new_instance.base.args = std::mem::take(&mut self.base.args);
+        new_instance.base.arg_count = self.base.arg_count;
         new_instance.set_exp_idx(self.get_exp_idx());
         Box::new(new_instance)
     }
-    fn is_operator(&self) -> bool {
+
+    fn takes_argument_list(&self) -> bool {
         true
     }
+
+    fn arg_count_range(&self) -> (i32, i32) {
+        VariadicFunctionUnit::arg_count_range(self)
+    }
+
+    fn set_arg_count(&mut self, count: i32) {
+        VariadicFunctionUnit::set_arg_count(self, count)
+    }
 }
 
-impl ExpUnit for SubOperator {
+impl ExcutableUnit for MultinomialFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        VariadicFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        VariadicFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for MultinomialFunc {
     fn to_string(&self) -> String {
-        BinaryFunctionUnit::to_string(self)
+        VariadicFunctionUnit::to_string(self)
     }
 
     fn exp_name(&self) -> &str {
-        EXP_UNIT_NAME_SUB
+        EXP_UNIT_NAME_MULTINOMIAL
     }
 
     fn get_exp_unit_base(&self) -> &ExpUnitBase {
@@ -1039,77 +6233,117 @@ impl ExpUnit for SubOperator {
     }
 }
 
-/// mul operator
-struct MulOperator {
-    base: BinaryFunctionBase,
+/// function defined by the user
+struct UserFunc {
+    base: VariadicFunctionBase,
+    name: String,
 }
 
-impl MulOperator {
-    pub fn new() -> Self {
+impl UserFunc {
+    pub fn new(name: &str, param_count: i32) -> Self {
         Self {
-            base: BinaryFunctionBase::new(ID_MUL, PRIODITY_MULTIPLICATIVE),
+            base: VariadicFunctionBase::new(ID_USER_FUNCTION, PRIODITY_USER_FUNCTION, param_count, param_count),
+            name: name.to_string(),
         }
     }    
 }
 
-impl BinaryFunctionUnit for MulOperator {
-    fn get_func_base(&self) -> &BinaryFunctionBase {
+impl VariadicFunctionUnit for UserFunc {
+    fn get_func_base(&self) -> &VariadicFunctionBase {
         &self.base
     }
 
-    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+    fn get_func_base_mut(&mut self) -> &mut VariadicFunctionBase {
         &mut self.base
     }
 
-    fn execute_with_args(&self, _1: f64, _2: f64) -> Result<f64, String> {
-        Ok(_1 * _2)
-    }
-}
+    fn execute_with_args(&self, args: &[Number]) -> Result<Number, CalcError> {
+        // the definition is looked up on every call, so redefining a function updates its callers
+        let def = Context::with_current(|c| c.borrow().functions.user_function(&self.name));
+        let def = def.ok_or(CalcError::UnknownFunction { name: self.name.clone(), exp_idx: self.get_exp_idx() })?;
+        if def.params.len() != args.len() {
+            let count = def.params.len() as i32;
+            return Err(CalcError::ArgumentCount {
+                name: self.name.clone(), count: args.len() as i32, min: count, max: count, exp_idx: self.get_exp_idx()
+            });
+        }
 
-impl ExcutableUnit for MulOperator {
-    fn execute(&self) -> Result<f64, String> {
-        BinaryFunctionUnit::execute(self)
+        Context::with_current(|c| {
+            let mut c = c.borrow_mut();
+            if c.call_frames.len() >= c.max_recursion_depth {
+                return Err(CalcError::RecursionLimit { depth: c.max_recursion_depth, exp_idx: self.get_exp_idx() });
+            }
+            c.call_frames.push(args.to_vec());
+            Ok(())
+        })?;
+        let v = def.body.execute();
+        Context::with_current(|c| c.borrow_mut().call_frames.pop());
+        v
     }
 }
 
-impl ExpOpUnit for MulOperator {
+impl ExpOpUnit for UserFunc {
     fn get_op_base(&self) -> &ExpOpBase {
-        BinaryFunctionUnit::get_op_base(self)
+        VariadicFunctionUnit::get_op_base(self)
     }
 
     fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
-        BinaryFunctionUnit::get_op_base_mut(self)
+        VariadicFunctionUnit::get_op_base_mut(self)
     }
 
     fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
-        BinaryFunctionUnit::push_operand(self, operand)
+        VariadicFunctionUnit::push_operand(self, operand)
     }
 
     fn arg_count(&self) -> i32 {
-        BinaryFunctionUnit::arg_count(self)
+        VariadicFunctionUnit::arg_count(self)
     }
 
-    
     fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
-        let mut new_instance = MulOperator::new();
-        new_instance.base._1 = self.base._1.take();
-        new_instance.base._2 = self.base._2.take();
+        let mut new_instance = UserFunc::new(&self.name, self.base.min_args);
+        new_instance.base.args = std::mem::take(&mut self.base.args);
+        new_instance.base.arg_count = self.base.arg_count;
         new_instance.set_exp_idx(self.get_exp_idx());
         Box::new(new_instance)
     }
 
-    fn is_operator(&self) -> bool {
+    fn takes_argument_list(&self) -> bool {
+        true
+    }
+
+    fn arg_count_range(&self) -> (i32, i32) {
+        VariadicFunctionUnit::arg_count_range(self)
+    }
+
+    fn set_arg_count(&mut self, count: i32) {
+        VariadicFunctionUnit::set_arg_count(self, count)
+    }
+
+    fn accepts_complex(&self) -> bool {
+        true
+    }
+    fn accepts_non_finite(&self) -> bool {
         true
     }
 }
 
-impl ExpUnit for MulOperator {
+impl ExcutableUnit for UserFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        VariadicFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        VariadicFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for UserFunc {
     fn to_string(&self) -> String {
-        BinaryFunctionUnit::to_string(self)
+        VariadicFunctionUnit::to_string(self)
     }
 
     fn exp_name(&self) -> &str {
-        EXP_UNIT_NAME_MUL
+        &self.name
     }
 
     fn get_exp_unit_base(&self) -> &ExpUnitBase {
@@ -1121,80 +6355,121 @@ impl ExpUnit for MulOperator {
     }
 }
 
-/// div operator
-struct DivOperator {
-    base: BinaryFunctionBase,
+/// definite integral ∫(f, x, a, b) of an expression in the variable x from a to b
+struct IntegralFunc {
+    base: VariadicFunctionBase,
 }
 
-impl DivOperator {
+impl IntegralFunc {
     pub fn new() -> Self {
         Self {
-            base: BinaryFunctionBase::new(ID_DIV, PRIODITY_MULTIPLICATIVE),
+            base: VariadicFunctionBase::new(ID_INTEGRAL, PRIODITY_UNARY_OP, 4, 4),
         }
     }    
 }
 
-impl BinaryFunctionUnit for DivOperator {
-    fn get_func_base(&self) -> &BinaryFunctionBase {
+impl VariadicFunctionUnit for IntegralFunc {
+    fn get_func_base(&self) -> &VariadicFunctionBase {
         &self.base
     }
 
-    fn get_func_base_mut(&mut self) -> &mut BinaryFunctionBase {
+    fn get_func_base_mut(&mut self) -> &mut VariadicFunctionBase {
         &mut self.base
     }
 
-    fn execute_with_args(&self, _1: f64, _2: f64) -> Result<f64, String> {
-        if _2 == 0.0 {
-            Err(String::from("Division by zero"))
-        } else {
-            Ok(_1 / _2)
-        }
+    // the expression is not a value, execute evaluates it for every value of the variable
+    fn execute_with_args(&self, _: &[Number]) -> Result<Number, CalcError> {
+        Err(CalcError::MissingOperand { exp_idx: self.get_exp_idx() })
     }
-}
 
-impl ExcutableUnit for DivOperator {
-    fn execute(&self) -> Result<f64, String> {
-        BinaryFunctionUnit::execute(self)
+    fn execute(&self) -> Result<Number, CalcError> {
+        let args = &self.get_func_base().args;
+        if args.len() < 4 {
+            return Err(CalcError::MissingOperand { exp_idx: self.get_exp_idx() });
+        }
+        let (a, b) = (args[2].execute()?, args[3].execute()?);
+        if a.is_complex() || b.is_complex() || !a.is_finite() || !b.is_finite() {
+            return Err(CalcError::Domain { exp_idx: self.get_exp_idx() });
+        }
+
+        // the variable follows the parameters of the function the integral is in
+        let (frame, tolerance) = Context::with_current(|c| {
+            let c = c.borrow();
+            (c.call_frames.last().cloned().unwrap_or_default(), c.integration_tolerance())
+        });
+        let f = |x: &Number| {
+            let mut values = frame.clone();
+            values.push(x.clone());
+            Context::with_current(|c| c.borrow_mut().call_frames.push(values));
+            let v = args[0].execute();
+            Context::with_current(|c| c.borrow_mut().call_frames.pop());
+            match v? {
+                v if v.is_complex() || !v.is_finite() => Err(CalcError::Domain { exp_idx: self.get_exp_idx() }),
+                v => Ok(v),
+            }
+        };
+        match math::integrate(f, &a, &b, &tolerance, Number::working_digits())? {
+            Some((v, _)) => v.round_result(self.get_exp_idx()),
+            None => Err(CalcError::ToleranceNotReached { exp_idx: self.get_exp_idx() }),
+        }
     }
 }
 
-impl ExpOpUnit for DivOperator {
+impl ExpOpUnit for IntegralFunc {
     fn get_op_base(&self) -> &ExpOpBase {
-        BinaryFunctionUnit::get_op_base(self)
+        VariadicFunctionUnit::get_op_base(self)
     }
 
     fn get_op_base_mut(&mut self) -> &mut ExpOpBase {
-        BinaryFunctionUnit::get_op_base_mut(self)
+        VariadicFunctionUnit::get_op_base_mut(self)
     }
 
     fn push_operand(&mut self, operand: Box<dyn ExcutableUnit>) -> i32 {
-        BinaryFunctionUnit::push_operand(self, operand)
+        VariadicFunctionUnit::push_operand(self, operand)
     }
 
     fn arg_count(&self) -> i32 {
-        BinaryFunctionUnit::arg_count(self)
+        VariadicFunctionUnit::arg_count(self)
     }
-    
+
     fn as_excutable_unit(&mut self) -> Box<dyn ExcutableUnit> {
-        let mut new_instance = DivOperator::new();
-        new_instance.base._1 = self.base._1.take();
-        new_instance.base._2 = self.base._2.take();
+        let mut new_instance = IntegralFunc::new();
+        new_instance.base.args = std::mem::take(&mut self.base.args);
+        new_instance.base.arg_count = self.base.arg_count;
         new_instance.set_exp_idx(self.get_exp_idx());
         Box::new(new_instance)
     }
 
-    fn is_operator(&self) -> bool {
+    fn takes_argument_list(&self) -> bool {
         true
     }
+
+    fn arg_count_range(&self) -> (i32, i32) {
+        VariadicFunctionUnit::arg_count_range(self)
+    }
+
+    fn set_arg_count(&mut self, count: i32) {
+        VariadicFunctionUnit::set_arg_count(self, count)
+    }
 }
 
-impl ExpUnit for DivOperator {
+impl ExcutableUnit for IntegralFunc {
+    fn execute(&self) -> Result<Number, CalcError> {
+        VariadicFunctionUnit::execute(self)
+    }
+
+    fn to_term(&self) -> Term {
+        VariadicFunctionUnit::to_term(self)
+    }
+}
+
+impl ExpUnit for IntegralFunc {
     fn to_string(&self) -> String {
-        BinaryFunctionUnit::to_string(self)
+        VariadicFunctionUnit::to_string(self)
     }
 
     fn exp_name(&self) -> &str {
-        EXP_UNIT_NAME_DIV
+        EXP_UNIT_NAME_INTEGRAL
     }
 
     fn get_exp_unit_base(&self) -> &ExpUnitBase {
@@ -1206,11 +6481,19 @@ impl ExpUnit for DivOperator {
     }
 }
 
+pub enum FunctorKind {
+    Prefix,
+    Postfix,
+    Infix,
+    // function which must be followed by a bracketed argument list
+    Function,
+}
 
 pub struct ExpressionBuilder {
     token_count: i32,
     operand_stack: Vec<Box<dyn ExcutableUnit>>,
     operator_stack: Vec<Box<dyn ExpOpUnit>>,
+    eval_immediately: bool,
 }
 
 impl ExpressionBuilder {
@@ -1218,10 +6501,18 @@ impl ExpressionBuilder {
         Self {
             token_count: 0,
             operator_stack: Vec::new(),
-            operand_stack: Vec::new(),            
+            operand_stack: Vec::new(),
+            eval_immediately: true,
         }
     }
 
+    /// builder which only builds the tree, intermediate results are not evaluated
+    pub fn new_deferred() -> Self {
+        let mut builder = Self::new();
+        builder.eval_immediately = false;
+        builder
+    }
+
     fn top_op(&self) -> Option<& Box<dyn ExpOpUnit>> {
         self.operator_stack.last()
     }
@@ -1230,16 +6521,16 @@ impl ExpressionBuilder {
         self.operator_stack.push(op);
     }
 
-    fn build_top_op_tree(&mut self, lower_bound_idx: i32) -> Result<Option<String>, String> {
+    fn build_top_op_tree(&mut self, lower_bound_idx: i32) -> Result<Option<String>, CalcError> {
         let mut op = self.operator_stack.pop().unwrap();        
         let mut args = op.arg_count();
         while args > 0 {
             if self.operand_stack.len() == 0 {
-                return Err("Invalid expression".to_string());
+                return Err(CalcError::MissingOperand { exp_idx: op.get_exp_idx() });
             }            
             let operand = self.operand_stack.pop().unwrap();
             if lower_bound_idx >= 0 && operand.get_exp_idx() <= lower_bound_idx {
-                return Err("Invalid expression".to_string());
+                return Err(CalcError::MissingOperand { exp_idx: op.get_exp_idx() });
             }
 
             op.push_operand(operand);
@@ -1247,6 +6538,11 @@ impl ExpressionBuilder {
             args -= 1;
         }
 
+        if !self.eval_immediately {
+            self.operand_stack.push(op.as_excutable_unit());
+            return Ok(None);
+        }
+
         let imediate_result = op.execute();
 
         // issue: https://github.com/rust-lang/rust/issues/65991
@@ -1257,26 +6553,100 @@ impl ExpressionBuilder {
         return imediate_result.map(|v| Some(v.to_string()));
     }
 
-    pub fn build_tree_inside_bracket(&mut self) -> Result<Option<String>, String> {
+    pub fn build_tree_inside_bracket(&mut self) -> Result<Option<String>, CalcError> {
         let x = self.operator_stack.iter().find(|op| op.get_op_base().id == ID_OPEN_BRACKET);
         let mut lower_bound_idx = if x.is_none() { -1 } else { x.unwrap().get_exp_idx() };
 
         while self.operator_stack.len() > 0 {
             let id = self.top_op().unwrap().get_op_base().id;
+            if ID_OPEN_BRACKET == id && self.is_argument_list() {
+                return self.build_function_call();
+            }
             let x = self.build_top_op_tree(lower_bound_idx);
             if x.is_err() {
                 return x;
             }
             if ID_OPEN_BRACKET == id {
+                // a function followed by a bracket takes the bracket as its whole operand, so sin(x)² = (sin(x))²
+                let is_function = self.top_op().is_some_and(|op| !op.is_operator() && op.arg_count() == 1);
+                if is_function && self.can_eval_unary_op() {
+                    return self.build_top_op_tree(-1);
+                }
                 return x;
             }
         }
-        Err("Missing open bracket".to_string())
+        Err(CalcError::MismatchedBrackets { exp_idx: self.token_count })
     }
 
-    pub fn just_return_imediate_result(&self) -> Result<Option<String>, String> {
+    // check if the bracket on top of the stack holds the arguments of a function call
+    fn is_argument_list(&self) -> bool {
+        let len = self.operator_stack.len();
+        if self.operator_stack[len - 1].has_arguments() {
+            return true;
+        }
+        len >= 2 && self.operator_stack[len - 2].takes_argument_list()
+    }
+
+    // build the function below the bracket on top of the stack with every argument inside the bracket
+    fn build_function_call(&mut self) -> Result<Option<String>, CalcError> {
+        let mut bracket = self.operator_stack.pop().unwrap();
+        let bracket_idx = bracket.get_exp_idx();
+        let mut args = bracket.take_arguments();
+
+        let has_last_arg = self.operand_stack.last().is_some_and(|operand| operand.get_exp_idx() > bracket_idx);
+        if has_last_arg {
+            args.push(self.operand_stack.pop().unwrap());
+        }
+        else if !args.is_empty() {
+            return Err(CalcError::MissingOperand { exp_idx: self.token_count });
+        }
+
+        let function = match self.top_op() {
+            Some(op) if op.takes_argument_list() || !op.is_operator() => op,
+            _ => return Err(CalcError::Syntax { token: EXP_UNIT_NAME_COMMA.to_string(), exp_idx: bracket_idx }),
+        };
+        let count = args.len() as i32;
+        let (min, max) = function.arg_count_range();
+        if count < min || count > max {
+            let name = function.exp_name().to_string();
+            return Err(CalcError::ArgumentCount { name, count, min, max, exp_idx: function.get_exp_idx() });
+        }
+
+        self.operand_stack.extend(args);
+        self.operator_stack.last_mut().unwrap().set_arg_count(count);
+        self.build_top_op_tree(-1)
+    }
+
+    // complete the argument before a comma and keep it in the nearest open bracket
+    fn add_argument(&mut self) -> Result<Option<String>, CalcError> {
+        let bracket = self.operator_stack.iter().rev().find(|op| op.get_op_base().id == ID_OPEN_BRACKET);
+        if bracket.is_none() {
+            return Err(CalcError::Syntax { token: EXP_UNIT_NAME_COMMA.to_string(), exp_idx: self.token_count });
+        }
+        let lower_bound_idx = bracket.unwrap().get_exp_idx();
+
+        while self.top_op().unwrap().get_op_base().id != ID_OPEN_BRACKET {
+            self.build_top_op_tree(lower_bound_idx)?;
+        }
+
+        let has_arg = self.operand_stack.last().is_some_and(|operand| operand.get_exp_idx() > lower_bound_idx);
+        if !has_arg {
+            return Err(CalcError::MissingOperand { exp_idx: self.token_count });
+        }
+        let arg = self.operand_stack.pop().unwrap();
+        let imediate_result = if self.eval_immediately { arg.execute().map(|v| Some(v.to_string())) } else { Ok(None) };
+        self.operator_stack.last_mut().unwrap().push_argument(arg);
+        imediate_result
+    }
+
+    /// position the next token will get
+    pub fn next_exp_idx(&self) -> i32 {
+        self.token_count + 1
+    }
+
+    pub fn just_return_imediate_result(&self) -> Result<Option<String>, CalcError> {
         match self.operand_stack.last() {
-            Some(op) => Ok(Some(op.execute().unwrap().to_string())),
+            Some(op) => Ok(Some(op.execute()?.to_string())),
             None => Ok(Some("0".to_string()))
         }
     }
@@ -1288,39 +6658,86 @@ impl ExpressionBuilder {
         self.operator_stack.push(op);
     }
 
-    pub fn push_functor(&mut self, name: String, allow_auto_complete: bool) -> Result<Option<String>, String> {
+    // check if the operator on top of the stack must be built before the given operator is pushed
+    fn should_build_top_op(&self, op: &dyn ExpOpUnit) -> bool {
+        match self.top_op() {
+            Some(top) => {
+                let top_precedence = top.get_op_base().precedence;
+                let precedence = op.get_op_base().precedence;
+                top_precedence < precedence || (top_precedence == precedence && !op.is_right_associative())
+            },
+            None => false
+        }
+    }
+
+    // build all operators on top of the stack which bind tighter than the given operator
+    fn build_tighter_op_trees(&mut self, op: &dyn ExpOpUnit) -> Result<Option<String>, CalcError> {
+        let mut x = None;
+        while self.should_build_top_op(op) {
+            x = self.build_top_op_tree(-1)?;
+        }
+        Ok(x)
+    }
+
+    // an operand or a bracket right after a complete operand multiplies it, e.g. 2π, 3(4+1) and (a)(b)
+    fn push_implicit_mul(&mut self) -> Result<Option<String>, CalcError> {
+        let mut op = ImplicitMulOperator::create();
+        self.token_count += 1;
+        op.set_exp_idx(self.token_count);
+
+        let x = self.build_tighter_op_trees(op.as_ref());
+        self.push_op(op);
+        x
+    }
+
+    pub fn push_functor(&mut self, name: String, allow_auto_complete: bool) -> Result<Option<String>, CalcError> {
+        if name == EXP_UNIT_NAME_OPEN_BRK && self.can_eval_unary_op() {
+            self.push_implicit_mul()?;
+        }
         self.token_count += 1;
 
         if name == EXP_UNIT_NAME_CLOSE_BRK { // close bracket
             return self.build_tree_inside_bracket();
         }        
 
-        let op_opt = EXP_OP_LIB.get_functor(&name);
+        if name == EXP_UNIT_NAME_COMMA {
+            return self.add_argument();
+        }
+
+        // a minus sign which does not follow an operand negates the next operand
+        let name = if name == EXP_UNIT_NAME_SUB && !self.can_eval_unary_op() {
+            EXP_UNIT_NAME_NEG.to_string()
+        }
+        else {
+            name
+        };
+
+        let op_opt = Context::with_current(|c| c.borrow().functions.get_functor(&name));
         if op_opt.is_none() {
-            return Err("No functor found".to_string());
+            return Err(CalcError::UnknownFunction { name, exp_idx: self.token_count });
         }
         let mut op = op_opt.unwrap();        
         op.set_exp_idx(self.token_count);
         let op_base = op.get_op_base();
 
-        let top_op = self.top_op();
-
         let prefer_eval = self.can_eval_unary_op();
 
+        // a function with an argument list takes the operand before it as its first argument, e.g. 8 logb is logb(8,
+        if prefer_eval && op.takes_argument_list() {
+            self.build_tighter_op_trees(op.as_ref())?;
+            let arg = self.operand_stack.pop().unwrap();
+            let imediate_result = arg.execute().map(|v| Some(v.to_string()));
+            self.push_op(op);
+            self.add_open_bracket();
+            self.operator_stack.last_mut().unwrap().push_argument(arg);
+            return imediate_result;
+        }
+
         // if the operator is a unary operator, and the caller want to evaluate it now                
         if prefer_eval && op.arg_count() == 1 && op_base.id != ID_OPEN_BRACKET {            
-            let op_base = op.get_op_base();
-            match top_op {
-                Some(top) => {
-                    let top_base = top.get_op_base();
-                    if top_base.precedence <= op_base.precedence {
-                        let x = self.build_top_op_tree(-1);
-                        if x.is_err() {
-                            return x;
-                        }
-                    }
-                },
-                None => {}
+            let x = self.build_tighter_op_trees(op.as_ref());
+            if x.is_err() {
+                return x;
             }
             self.push_op(op);
             return self.build_top_op_tree(-1);
@@ -1336,16 +6753,16 @@ impl ExpressionBuilder {
         }
 
         
-        match top_op {
-            Some(top) => {
-                let top_base = top.get_op_base();
+        match self.top_op() {
+            Some(_) => {
                 if op_base.id == ID_OPEN_BRACKET {
                     self.push_op(op);
                     return Ok(None);
                 }
 
-                if top_base.precedence <= op_base.precedence {
-                    let x = self.build_top_op_tree(-1);
+                // a unary function here starts a new operand, so there is nothing to build yet
+                if op.arg_count() != 1 && self.should_build_top_op(op.as_ref()) {
+                    let x = self.build_tighter_op_trees(op.as_ref());
                     self.push_op(op);            
                     return x;
                 }                                
@@ -1389,17 +6806,57 @@ impl ExpressionBuilder {
         should_auto_complete
     }
 
-    pub fn push_operand(&mut self, token: String) -> bool {
+    // push an operand, a complete operand before it is multiplied with it
+    // two numbers next to each other are not a product, e.g. 3 2 is an error while 2 π and (3)2 multiply
+    fn push_leaf(&mut self, mut operand: Box<dyn ExcutableUnit>) -> bool {
+        if self.can_eval_unary_op() {
+            let top_operand = self.operand_stack.last().unwrap();
+            if Self::is_number_leaf(top_operand.as_ref()) && Self::is_number_leaf(operand.as_ref()) {
+                return false;
+            }
+            // the intermediate result does not matter here, the tree is built either way
+            let _ = self.push_implicit_mul();
+        }
         self.token_count += 1;
+        operand.set_exp_idx(self.token_count);
+        self.operand_stack.push(operand);
+        true
+    }
+
+    fn is_number_leaf(unit: &dyn ExcutableUnit) -> bool {
+        unit.is_leaf() && matches!(unit.to_term(), Term::Constant { name: None, .. })
+    }
+
+    pub fn push_constant(&mut self, name: String, value: &str) -> bool {
+        match value.parse::<Number>() {
+            Err(_) => false,
+            Ok(value) => {
+                let mut operand = ConstantUnit::new(value);
+                operand.name = Some(name);
+                self.push_leaf(Box::new(operand))
+            }
+        }
+    }
+
+    pub fn push_variable(&mut self, name: String) {
+        self.push_leaf(Box::new(VariableUnit::new(name)));
+    }
+
+    pub fn push_parameter(&mut self, name: String, index: usize) {
+        self.push_leaf(Box::new(ParameterUnit::new(name, index)));
+    }
 
-        let res = token.parse::<f64>();
+    pub fn push_operand(&mut self, token: String) -> bool {
+        let res = token.parse::<Number>();
         match res {
             Err(_) => false,
             Ok(value) => {
-                let mut operand = Box::new(ConstantUnit::new(value));
-                operand.set_exp_idx(self.token_count);
-                self.operand_stack.push(operand);
-                true
+                let mut operand = ConstantUnit::new(value);
+                // integers keep the base they are written in, and numbers their exponent
+                if Self::is_radix_integer(&token) || token.contains(['e', 'E']) {
+                    operand.literal = Some(token);
+                }
+                self.push_leaf(Box::new(operand))
             }
         }
     }
@@ -1425,26 +6882,36 @@ impl ExpressionBuilder {
         });
 
         for (is_operand, idx) in exp_indices {
-            if is_operand {
-                exp_str += &self.operand_stack[idx].to_string();
+            let unit_str = if is_operand {
+                self.operand_stack[idx].to_string()
             }
             else {
-                exp_str += &self.operator_stack[idx].to_string();
-            }
+                self.operator_stack[idx].to_string()
+            };
+            exp_str = juxtapose(&exp_str, &unit_str);
         }
 
         exp_str
     }
 
-    pub fn finish(&mut self) -> Result<Expression, String> {
+    pub fn finish(&mut self) -> Result<Expression, CalcError> {
+        // close the brackets which are left open, so the function calls get all of their arguments
+        while self.operator_stack.iter().any(|op| op.get_op_base().id == ID_OPEN_BRACKET) {
+            self.build_tree_inside_bracket()?;
+        }
         while self.operator_stack.len() > 0 {
             let x = self.build_top_op_tree(-1);
             if x.is_err() {
                 return Err(x.err().unwrap());
             }
         }
-        if self.operand_stack.len() != 1 {
-            return Err("Invalid expression".to_string());
+        match self.operand_stack.len() {
+            0 => return Err(CalcError::EmptyExpression),
+            1 => {},
+            _ => {
+                let operand = &self.operand_stack[1];
+                return Err(CalcError::Syntax { token: operand.to_string(), exp_idx: operand.get_exp_idx() });
+            }
         }
         Ok(Expression {
             root: self.operand_stack.pop(),
@@ -1452,27 +6919,63 @@ impl ExpressionBuilder {
     }
 
     pub fn tokenize(input: String) -> Vec<String> {
-        let mut tokens = Vec::new();
-        let mut token = String::new();
-        for c in input.chars() {
-            if c.is_alphanumeric() || c == '.' {
-                token.push(c);
+        Lexer::new(&input).map(|t| t.text).collect()
+    }
+
+    pub fn is_functor(name: &str) -> bool {
+        Context::with_current(|c| c.borrow().functions.contains(name))
+    }
+
+    pub fn functor_kind(name: &str) -> Option<FunctorKind> {
+        let op_opt = Context::with_current(|c| c.borrow().functions.get_functor(&name.to_string()));
+        op_opt.map(|op| {
+            if op.takes_argument_list() {
+                FunctorKind::Function
+            }
+            else if op.arg_count() == 2 {
+                FunctorKind::Infix
+            }
+            else if op.is_postfix() {
+                FunctorKind::Postfix
             }
             else {
-                if !token.is_empty() {
-                    tokens.push(token);
-                    token = String::new();
-                }
-                tokens.push(c.to_string());
+                FunctorKind::Prefix
             }
-        }
-        if !token.is_empty() {
-            tokens.push(token.clone());
-        }
-        tokens
+        })
+    }
+
+    /// fraction literal such as "7/12" or the mixed number "1 5/12"
+    pub fn is_fraction(s: &str) -> bool {
+        s.contains('/') && s.parse::<Number>().is_ok()
+    }
+
+    /// complex literal such as "3+4i" or "i"
+    pub fn is_complex(s: &str) -> bool {
+        s.ends_with('i') && s.parse::<Number>().is_ok()
+    }
+
+    /// integer literal with a base prefix such as "0xFF", "0o17" or "0b101"
+    pub fn is_radix_integer(s: &str) -> bool {
+        s.contains(['x', 'X', 'o', 'O', 'b', 'B']) && s.parse::<Number>().is_ok()
     }
 
+    /// ∞, -∞ or NaN, the values of the IEEE passthrough
+    pub fn is_non_finite(s: &str) -> bool {
+        matches!(s, "∞" | "-∞" | "NaN")
+    }
+
+    /// decimal, fraction, complex, prefixed integer or non-finite literal
+    pub fn is_number(s: &str) -> bool {
+        Self::is_decimal(s) || Self::is_fraction(s) || Self::is_complex(s) || Self::is_radix_integer(s) || Self::is_non_finite(s)
+    }
+
+    /// decimal literal, optionally with an exponent such as "6.022e23" or "1.5e-3"
     pub fn is_decimal(s : &str) -> bool {
+        if let Some(i) = s.find(['e', 'E']) {
+            let exponent = &s[i + 1..];
+            let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            return !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) && Self::is_decimal(&s[..i]);
+        }
         if s.is_empty() {
             return false;
         }    
@@ -1545,6 +7048,54 @@ impl ExpOpLib {
         op_creator_map.insert(EXP_UNIT_NAME_INV.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(InvFunc::new()) });
         op_creator_map.insert(EXP_UNIT_NAME_SQR.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(SquareFunc::new()) });
         op_creator_map.insert(EXP_UNIT_NAME_SQRT.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(SqrtFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_POW.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(PowOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ROOT.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(RootOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_MOD.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(ModOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_INT_DIV.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(IntDivOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_REM.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(RemOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_NEG.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(NegFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_LN.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(LnFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_LOG10.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(Log10Func::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_LOG.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(Log10Func::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_LOG2.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(Log2Func::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_LOGB.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(LogbFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_EXP.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(ExpFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_EXP10.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(Exp10Func::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ABS.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(AbsFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ASIN.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(AsinFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ACOS.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(AcosFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ATAN.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(AtanFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ATAN2.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(Atan2Func::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_SINH.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(SinhFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_COSH.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(CoshFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_TANH.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(TanhFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ASINH.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(AsinhFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ACOSH.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(AcoshFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ATANH.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(AtanhFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_FACTORIAL.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(FactorialFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_DOUBLE_FACTORIAL.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(DoubleFactorialFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_GAMMA.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(GammaFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_PERMUTATIONS.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(PermutationsOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_COMBINATIONS.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(CombinationsOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_MIN.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(MinFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_MAX.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(MaxFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_HYPOT.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(HypotFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_GCD.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(GcdFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_SUM.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(SumFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_MULTINOMIAL.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(MultinomialFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_RE.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(ReFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_IM.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(ImFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ARG.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(ArgFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_CONJ.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(ConjFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_AND.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(AndOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_OR.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(OrOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_XOR.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(XorOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_NOT.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(NotFunc::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_SHL.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(ShlOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_SHR.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(ShrOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ROL.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(RolOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_ROR.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(RorOperator::new()) });
+        op_creator_map.insert(EXP_UNIT_NAME_INTEGRAL.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(IntegralFunc::new()) });
         op_creator_map.insert(EXP_UNIT_NAME_OPEN_BRK.to_string(), |_: &String| -> Box<dyn ExpOpUnit> { Box::new(CollectOperator::new()) });
         
         Self {
@@ -1561,4 +7112,78 @@ impl ExpOpLib {
 
 lazy_static! {
     static ref EXP_OP_LIB: ExpOpLib = ExpOpLib::new();
+}
+/// function defined by the user, the body refers to the parameters by their index
+pub struct UserFunctionDef {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Expression,
+}
+
+impl std::fmt::Display for UserFunctionDef {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}({}) = {}", self.name, self.params.join(", "), self.body.to_string())
+    }
+}
+
+/// functions known to a calculator, the built-in library plus the user functions
+pub struct FunctionRegistry {
+    op_creator_map: HashMap<String, ExpOpCreator>,
+    user_functions: HashMap<String, Rc<UserFunctionDef>>,
+}
+
+impl Default for FunctionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FunctionRegistry {
+    pub fn new() -> Self {
+        Self {
+            op_creator_map: EXP_OP_LIB.op_creator_map.clone(),
+            user_functions: HashMap::new(),
+        }
+    }
+
+    pub fn get_functor(&self, name: &String) -> Option<Box<dyn ExpOpUnit>> {
+        match self.user_functions.get(name) {
+            Some(def) => Some(Box::new(UserFunc::new(name, def.params.len() as i32))),
+            None => self.op_creator_map.get(name).map(|op_creator| op_creator(name)),
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.user_functions.contains_key(name) || self.op_creator_map.contains_key(name)
+    }
+
+    pub fn is_built_in(&self, name: &str) -> bool {
+        self.op_creator_map.contains_key(name)
+    }
+
+    pub fn user_function(&self, name: &str) -> Option<Rc<UserFunctionDef>> {
+        self.user_functions.get(name).cloned()
+    }
+
+    /// add or replace a user function, returns the replaced definition
+    pub fn define(&mut self, def: UserFunctionDef) -> Option<Rc<UserFunctionDef>> {
+        self.user_functions.insert(def.name.clone(), Rc::new(def))
+    }
+
+    pub fn restore(&mut self, name: &str, def: Option<Rc<UserFunctionDef>>) {
+        match def {
+            Some(def) => self.user_functions.insert(name.to_string(), def),
+            None => self.user_functions.remove(name),
+        };
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.user_functions.remove(name).is_some()
+    }
+
+    pub fn user_functions(&self) -> Vec<Rc<UserFunctionDef>> {
+        let mut defs: Vec<Rc<UserFunctionDef>> = self.user_functions.values().cloned().collect();
+        defs.sort_by(|a, b| a.name.cmp(&b.name));
+        defs
+    }
 }
\ No newline at end of file
@@ -0,0 +1,204 @@
+use super::context::RoundingMode;
+use super::number::Number;
+
+// decimals of the fixed and scientific modes when they are switched on from the keypad
+pub const DEFAULT_DECIMALS: usize = 4;
+// in the auto mode, numbers below 10^-AUTO_MIN_EXPONENT are written in scientific notation
+const AUTO_MIN_EXPONENT: i64 = 6;
+// and numbers with more integer digits than AUTO_MAX_INTEGER_DIGITS as well
+const AUTO_MAX_INTEGER_DIGITS: i64 = 12;
+
+/// how results are written on the display
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DisplayMode {
+    // plain numbers, scientific notation for the ones which are too large or too small
+    Auto,
+    // fixed number of decimals, e.g. FIX 2 writes π as 3.14
+    Fix(usize),
+    // scientific notation with a fixed number of decimals, e.g. SCI 2 writes 1234 as 1.23e3
+    Sci(usize),
+    // scientific notation with an exponent which is a multiple of 3, e.g. 12.5e3
+    Eng,
+}
+
+impl std::fmt::Display for DisplayMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DisplayMode::Auto => write!(f, "AUTO"),
+            DisplayMode::Fix(n) => write!(f, "FIX {}", n),
+            DisplayMode::Sci(n) => write!(f, "SCI {}", n),
+            DisplayMode::Eng => write!(f, "ENG"),
+        }
+    }
+}
+
+/// separators of the digits, e.g. 1,234.5 in English and 1.234,5 in German
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Separators {
+    pub group: char,
+    pub decimal: char,
+}
+
+impl Default for Separators {
+    fn default() -> Self {
+        Self { group: ',', decimal: '.' }
+    }
+}
+
+impl Separators {
+    /// separators of a locale such as "de_DE.UTF-8" or "fr-CA", the English ones for an unknown locale
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale.split(['_', '-', '.', '@']).next().unwrap_or("").to_lowercase();
+        let (group, decimal) = match language.as_str() {
+            "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl" | "sr" => ('.', ','),
+            "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "uk" | "hu" | "bg" | "lt" | "lv" | "et" => ('\u{a0}', ','),
+            _ => return Self::default(),
+        };
+        Self { group, decimal }
+    }
+
+    /// separators of the locale of the user, taken from LC_ALL, LC_NUMERIC or LANG
+    pub fn from_env() -> Self {
+        let locale = ["LC_ALL", "LC_NUMERIC", "LANG"].iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|locale| !locale.is_empty());
+        Self::for_locale(&locale.unwrap_or_default())
+    }
+}
+
+/// how numbers are written on the display, the values themselves keep all of their digits
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct NumberFormat {
+    pub mode: DisplayMode,
+    // significant digits of the auto and engineering modes, the working precision when it is None
+    pub significant_digits: Option<u64>,
+    // separate the thousands of the integer part
+    pub grouping: bool,
+    pub separators: Separators,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            mode: DisplayMode::Auto,
+            significant_digits: None,
+            grouping: false,
+            separators: Separators::default(),
+        }
+    }
+}
+
+impl NumberFormat {
+    /// write a number with at most the working precision of significant digits, fractions, ∞ and NaN stay as they are
+    pub fn format(&self, v: &Number, precision: u64, rounding: RoundingMode) -> String {
+        if v.is_complex() {
+            return self.format_complex(v, precision, rounding);
+        }
+        if v.is_fraction() || !v.is_finite() {
+            return v.to_string();
+        }
+        let digits = self.significant_digits.map_or(precision, |d| d.min(precision)).max(1);
+        match self.mode {
+            DisplayMode::Auto => {
+                let (negative, d, e) = v.to_digits(digits, rounding);
+                if e >= AUTO_MAX_INTEGER_DIGITS.min(digits as i64) || e < -AUTO_MIN_EXPONENT {
+                    return self.scientific(negative, &d, e, 0);
+                }
+                self.plain(negative, &d, e, 0)
+            },
+            DisplayMode::Fix(n) => {
+                let (negative, d, e) = v.to_fixed_digits(n as i64, rounding);
+                // an integer part longer than the precision does not fit
+                if e >= digits as i64 {
+                    let (negative, d, e) = v.to_digits(digits, rounding);
+                    return self.scientific(negative, &d, e, 0);
+                }
+                self.plain(negative, &d, e, n)
+            },
+            DisplayMode::Sci(n) => {
+                let (negative, d, e) = v.to_digits(n as u64 + 1, rounding);
+                self.scientific(negative, &d, e, n)
+            },
+            DisplayMode::Eng => {
+                let (negative, d, e) = v.to_digits(digits, rounding);
+                // zero has no exponent
+                if v.is_zero() {
+                    return self.plain(negative, &d, e, 0);
+                }
+                let shift = e.rem_euclid(3);
+                format!("{}e{}", self.plain(negative, &d, shift, 0), e - shift)
+            },
+        }
+    }
+
+    // a+bi with both parts in the display format
+    fn format_complex(&self, v: &Number, precision: u64, rounding: RoundingMode) -> String {
+        let (re, im) = (v.re(), v.im());
+        let mut text = String::new();
+        if !re.is_zero() {
+            text.push_str(&self.format(&re, precision, rounding));
+            if !im.is_negative() {
+                text.push('+');
+            }
+        }
+        if im.is_negative() {
+            text.push('-');
+        }
+        let im = im.abs();
+        if im != Number::one() {
+            text.push_str(&self.format(&im, precision, rounding));
+        }
+        text.push('i');
+        text
+    }
+
+    // digits written without an exponent, with at least the given number of decimals
+    fn plain(&self, negative: bool, digits: &str, exponent: i64, min_decimals: usize) -> String {
+        let (integer, mut decimals) = if exponent < 0 {
+            ("0".to_string(), "0".repeat((-exponent - 1) as usize) + digits)
+        }
+        else {
+            let length = exponent as usize + 1;
+            if digits.len() <= length {
+                (format!("{:0<length$}", digits), String::new())
+            }
+            else {
+                (digits[..length].to_string(), digits[length..].to_string())
+            }
+        };
+        while decimals.len() < min_decimals {
+            decimals.push('0');
+        }
+
+        let mut text = String::new();
+        if negative {
+            text.push('-');
+        }
+        text.push_str(&self.group(&integer));
+        if !decimals.is_empty() {
+            text.push(self.separators.decimal);
+            text.push_str(&decimals);
+        }
+        text
+    }
+
+    // one digit before the decimal separator and the exponent, e.g. 6.022e23
+    fn scientific(&self, negative: bool, digits: &str, exponent: i64, min_decimals: usize) -> String {
+        format!("{}e{}", self.plain(negative, digits, 0, min_decimals), exponent)
+    }
+
+    // separate the thousands of an integer part
+    fn group(&self, integer: &str) -> String {
+        if !self.grouping {
+            return integer.to_string();
+        }
+        let mut text = String::new();
+        for (i, c) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i).is_multiple_of(3) {
+                text.push(self.separators.group);
+            }
+            text.push(c);
+        }
+        text
+    }
+}
@@ -17,8 +17,61 @@ pub const ID_OPEN_BRACKET: FunctionId = 13;
 pub const ID_CLOSE_BRACKET: FunctionId = 14;
 pub const ID_SQR: FunctionId = 15;
 pub const ID_INV: FunctionId = 16;
+pub const ID_ROOT: FunctionId = 17;
+pub const ID_INT_DIV: FunctionId = 18;
+pub const ID_REM: FunctionId = 19;
+pub const ID_LOG10: FunctionId = 20;
+pub const ID_LOG2: FunctionId = 21;
+pub const ID_LOGB: FunctionId = 22;
+pub const ID_EXP: FunctionId = 23;
+pub const ID_EXP10: FunctionId = 24;
+pub const ID_ASIN: FunctionId = 25;
+pub const ID_ACOS: FunctionId = 26;
+pub const ID_ATAN: FunctionId = 27;
+pub const ID_ATAN2: FunctionId = 28;
+pub const ID_SINH: FunctionId = 29;
+pub const ID_COSH: FunctionId = 30;
+pub const ID_TANH: FunctionId = 31;
+pub const ID_ASINH: FunctionId = 32;
+pub const ID_ACOSH: FunctionId = 33;
+pub const ID_ATANH: FunctionId = 34;
+pub const ID_FACTORIAL: FunctionId = 35;
+pub const ID_DOUBLE_FACTORIAL: FunctionId = 36;
+pub const ID_GAMMA: FunctionId = 37;
+pub const ID_PERMUTATIONS: FunctionId = 38;
+pub const ID_COMBINATIONS: FunctionId = 39;
+pub const ID_MIN: FunctionId = 40;
+pub const ID_MAX: FunctionId = 41;
+pub const ID_HYPOT: FunctionId = 42;
+pub const ID_GCD: FunctionId = 43;
+pub const ID_SUM: FunctionId = 44;
+pub const ID_MULTINOMIAL: FunctionId = 45;
+pub const ID_USER_FUNCTION: FunctionId = 46;
+pub const ID_IMPLICIT_MUL: FunctionId = 47;
+pub const ID_RE: FunctionId = 48;
+pub const ID_IM: FunctionId = 49;
+pub const ID_ARG: FunctionId = 50;
+pub const ID_CONJ: FunctionId = 51;
+pub const ID_AND: FunctionId = 52;
+pub const ID_OR: FunctionId = 53;
+pub const ID_XOR: FunctionId = 54;
+pub const ID_NOT: FunctionId = 55;
+pub const ID_SHL: FunctionId = 56;
+pub const ID_SHR: FunctionId = 57;
+pub const ID_ROL: FunctionId = 58;
+pub const ID_ROR: FunctionId = 59;
+pub const ID_INTEGRAL: FunctionId = 60;
 
-pub const PRIODITY_ADDITIVE: i32 = 6;
-pub const PRIODITY_MULTIPLICATIVE: i32 = 5;
+pub const PRIODITY_POSTFIX_OP: i32 = 0;
+pub const PRIODITY_POWER: i32 = 1;
+pub const PRIODITY_ADDITIVE: i32 = 7;
+pub const PRIODITY_MULTIPLICATIVE: i32 = 6;
 pub const PRIODITY_USER_FUNCTION: i32 = 2;
 pub const PRIODITY_UNARY_OP: i32 = 3;
+// nPr and nCr, a negative operand is taken as a whole, e.g. -5 nCr 2 is (-5) nCr 2
+pub const PRIODITY_COMBINATORIAL: i32 = 4;
+pub const PRIODITY_IMPLICIT_MUL: i32 = 5;
+pub const PRIODITY_SHIFT: i32 = 8;
+pub const PRIODITY_BITWISE_AND: i32 = 9;
+pub const PRIODITY_BITWISE_XOR: i32 = 10;
+pub const PRIODITY_BITWISE_OR: i32 = 11;
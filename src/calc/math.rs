@@ -0,0 +1,481 @@
+use std::str::FromStr;
+
+use super::context::{AngleMode, RoundingMode};
+use super::error::CalcError;
+use super::number::{Number, MAX_EXPONENT};
+
+fn full_turn(mode: AngleMode, digits: u64) -> Number {
+    match mode {
+        AngleMode::Degree => Number::from(360),
+        AngleMode::Radian => Number::pi(digits) * Number::from(2),
+        AngleMode::Gradian => Number::from(400),
+    }
+}
+
+pub fn to_radians(x: &Number, mode: AngleMode, digits: u64) -> Number {
+    if mode == AngleMode::Radian {
+        return x.clone();
+    }
+    (x * &full_turn(AngleMode::Radian, digits)).div(&full_turn(mode, digits), digits)
+}
+
+pub fn from_radians(x: &Number, mode: AngleMode, digits: u64) -> Number {
+    if mode == AngleMode::Radian {
+        return x.clone();
+    }
+    (x * &full_turn(mode, digits)).div(&full_turn(AngleMode::Radian, digits), digits)
+}
+
+/// sine of an angle, exact at the multiples of 30° unless the angle is in radians
+pub fn sin(x: &Number, mode: AngleMode, digits: u64) -> Number {
+    if mode == AngleMode::Radian {
+        return x.sin(digits);
+    }
+
+    // reduce the angle to degrees in the first quadrant so that exact angles stay exact
+    let mut degrees = if mode == AngleMode::Gradian { x * &Number::from_str("0.9").unwrap() } else { x.clone() };
+    degrees = degrees.rem(&Number::from(360));
+    if degrees.is_negative() {
+        degrees = degrees + Number::from(360);
+    }
+    let mut negative = false;
+    if degrees >= Number::from(180) {
+        degrees = degrees - Number::from(180);
+        negative = true;
+    }
+    if degrees > Number::from(90) {
+        degrees = Number::from(180) - degrees;
+    }
+
+    let v = if degrees.is_zero() {
+        Number::zero()
+    }
+    else if degrees == Number::from(30) {
+        Number::from_str("0.5").unwrap()
+    }
+    else if degrees == Number::from(90) {
+        Number::one()
+    }
+    else {
+        to_radians(&degrees, AngleMode::Degree, digits + 2).sin(digits)
+    };
+    if negative { -v } else { v }
+}
+
+pub fn cos(x: &Number, mode: AngleMode, digits: u64) -> Number {
+    if mode == AngleMode::Radian {
+        return x.cos(digits);
+    }
+    sin(&(x + &full_turn(mode, digits).div(&Number::from(4), digits)), mode, digits)
+}
+
+fn asin_radians(x: &Number, digits: u64) -> Number {
+    if x.abs() == Number::one() {
+        let v = Number::pi(digits).div(&Number::from(2), digits);
+        return if x.is_negative() { -v } else { v };
+    }
+    // asin x = atan(x / √(1 − x²))
+    let work = digits + 2;
+    x.div(&(Number::one() - x * x).sqrt(work), work).atan(digits)
+}
+
+fn acos_radians(x: &Number, digits: u64) -> Number {
+    if *x == -Number::one() {
+        return Number::pi(digits);
+    }
+    // acos x = 2·atan(√((1 − x) / (1 + x))), accurate near 1 as well
+    let work = digits + 2;
+    let t = (Number::one() - x).div(&(Number::one() + x), work).sqrt(work);
+    t.atan(work) * Number::from(2)
+}
+
+// angle of an exact inverse sine, as the number of such angles in a full turn
+fn exact_asin(x: &Number) -> Option<i64> {
+    let x = x.abs();
+    if x.is_zero() {
+        Some(0)
+    }
+    else if x == Number::from_str("0.5").unwrap() {
+        Some(12)
+    }
+    else if x == Number::one() {
+        Some(4)
+    }
+    else {
+        None
+    }
+}
+
+/// inverse sine, exact at the sines of the multiples of 30° unless the angle is in radians
+pub fn asin(x: &Number, mode: AngleMode, digits: u64) -> Number {
+    if mode == AngleMode::Radian {
+        return asin_radians(x, digits);
+    }
+
+    let angle = match exact_asin(x) {
+        Some(0) => Number::zero(),
+        Some(parts) => full_turn(mode, digits).div(&Number::from(parts), digits),
+        None => from_radians(&asin_radians(&x.abs(), digits + 2), mode, digits),
+    };
+    if x.is_negative() { -angle } else { angle }
+}
+
+pub fn acos(x: &Number, mode: AngleMode, digits: u64) -> Number {
+    if mode == AngleMode::Radian {
+        return acos_radians(x, digits);
+    }
+    if exact_asin(x).is_none() {
+        return from_radians(&acos_radians(x, digits + 2), mode, digits);
+    }
+    full_turn(mode, digits).div(&Number::from(4), digits) - asin(x, mode, digits)
+}
+
+/// inverse tangent, exact at 0 and ±1 unless the angle is in radians
+pub fn atan(x: &Number, mode: AngleMode, digits: u64) -> Number {
+    if mode == AngleMode::Radian {
+        return x.atan(digits);
+    }
+
+    let angle = if x.abs() == Number::one() {
+        full_turn(mode, digits).div(&Number::from(8), digits)
+    }
+    else if x.is_zero() {
+        Number::zero()
+    }
+    else {
+        from_radians(&x.abs().atan(digits + 2), mode, digits)
+    };
+    if x.is_negative() { -angle } else { angle }
+}
+
+/// angle of the point (x, y), both must not be 0
+pub fn atan2(y: &Number, x: &Number, mode: AngleMode, digits: u64) -> Number {
+    let half_turn = full_turn(mode, digits + 2).div(&Number::from(2), digits + 2);
+    if x.is_zero() {
+        let v = half_turn.div(&Number::from(2), digits);
+        return if y.is_negative() { -v } else { v };
+    }
+    let v = atan(&y.div(x, digits + 2), mode, digits + 2);
+    if !x.is_negative() {
+        v
+    }
+    else if y.is_negative() {
+        v - half_turn
+    }
+    else {
+        v + half_turn
+    }
+}
+
+/// gamma function by Spouge's approximation, the reflection formula is used below 0.5, None on overflow
+pub fn gamma(x: &Number, digits: u64) -> Option<Number> {
+    if x.is_integer() && x.is_positive() {
+        return factorial(&(x - &Number::one()), digits);
+    }
+    if *x < Number::from_str("0.5").unwrap() {
+        // Γ(x) = π / (sin(πx)·Γ(1 − x))
+        let work = digits + 2 + x.magnitude().max(0) as u64;
+        let pi = Number::pi(work);
+        let g = gamma(&(Number::one() - x), work)?;
+        return Some(pi.div(&((&pi * x).sin(work) * g), digits));
+    }
+
+    // Γ(z + 1) = (z + a)^(z + ½)·e^(−(z + a))·(c0 + Σ c_k / (z + k)) with a relative error below (2π)^−a,
+    // the terms of the sum cancel each other, so it is computed with twice the digits
+    let a = digits * 13 / 10 + 2;
+    let work = digits * 2 + 10;
+    let z = x - &Number::one();
+    let e = Number::one().exp(work)?;
+
+    let mut sum = (Number::pi(work) * Number::from(2)).sqrt(work);
+    let mut k_factorial = Number::one();
+    for k in 1..a {
+        let ak = Number::from((a - k) as i64);
+        // c_k = (−1)^(k−1)·(a − k)^(k − ½)·e^(a − k) / (k − 1)!
+        let c = ak.pow(&Number::from(k as i64 - 1), work)? * ak.sqrt(work) * e.pow(&ak, work)?;
+        let term = c.div(&(&k_factorial * &(&z + &Number::from(k as i64))), work);
+        sum = if k % 2 == 1 { sum + term } else { sum - term };
+        k_factorial = k_factorial * Number::from(k as i64);
+    }
+
+    let za = &z + &Number::from(a as i64);
+    let half = Number::from_str("0.5").unwrap();
+    let power = ((&z + &half) * za.ln(work) - za).exp(work)?;
+    Some((power * sum).round_to(digits, RoundingMode::HalfEven))
+}
+
+/// factorial of a non-negative integer, None on overflow
+pub fn factorial(n: &Number, digits: u64) -> Option<Number> {
+    // beyond 3249! the result is out of range anyway
+    let n = n.to_i64().filter(|n| *n <= 5000)?;
+    let mut v = Number::one();
+    for i in 2..=n {
+        v = (v * Number::from(i)).round_to(digits, RoundingMode::HalfEven);
+        if v.magnitude() > MAX_EXPONENT {
+            return None;
+        }
+    }
+    Some(v)
+}
+
+/// double factorial n×(n−2)×(n−4)×..., defined for integers from -1, None on overflow
+pub fn double_factorial(n: &Number, digits: u64) -> Option<Number> {
+    let mut i = n.to_i64().filter(|n| *n <= 10000)?;
+    let mut v = Number::one();
+    while i > 1 {
+        v = (v * Number::from(i)).round_to(digits, RoundingMode::HalfEven);
+        if v.magnitude() > MAX_EXPONENT {
+            return None;
+        }
+        i -= 2;
+    }
+    Some(v)
+}
+
+/// number of ordered selections of r items out of n, n×(n−1)×...×(n−r+1), None on overflow
+pub fn permutations(n: &Number, r: &Number, digits: u64) -> Option<Number> {
+    let mut v = Number::one();
+    let mut i = Number::zero();
+    while i < *r {
+        v = (v * (n - &i)).round_to(digits, RoundingMode::HalfEven);
+        if v.magnitude() > MAX_EXPONENT {
+            return None;
+        }
+        i = i + Number::one();
+    }
+    Some(v)
+}
+
+/// number of unordered selections of r items out of n, None on overflow
+pub fn combinations(n: &Number, r: &Number, digits: u64) -> Option<Number> {
+    // each step is C(n−r+i, i) which is an integer, so the division never leaves a fraction behind
+    let n_r = n - r;
+    let r = if n_r < *r { n_r.clone() } else { r.clone() };
+    let mut v = Number::one();
+    let mut i = Number::one();
+    while i <= r {
+        v = (v * (n - &r + &i)).div(&i, digits);
+        if v.magnitude() > MAX_EXPONENT {
+            return None;
+        }
+        i = i + Number::one();
+    }
+    Some(v)
+}
+
+/// greatest common divisor of two integers
+pub fn gcd(a: &Number, b: &Number) -> Number {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while !b.is_zero() {
+        let r = a.rem(&b);
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// number of ways to split the sum of the given counts into groups of these sizes, None on overflow
+pub fn multinomial(counts: &[Number], digits: u64) -> Option<Number> {
+    // product of C(k1 + ... + ki, ki), every factor is an integer
+    let mut v = Number::one();
+    let mut n = Number::zero();
+    for k in counts {
+        n = n + k;
+        v = (v * combinations(&n, k, digits)?).round_to(digits, RoundingMode::HalfEven);
+        if v.magnitude() > MAX_EXPONENT {
+            return None;
+        }
+    }
+    Some(v)
+}
+
+/// nth root, exact when the root is representable at the working precision, None on overflow
+pub fn root(x: &Number, n: &Number, precision: u64, digits: u64) -> Option<Number> {
+    if *n == Number::from(2) {
+        let v = x.abs().sqrt(digits);
+        return Some(if x.is_negative() { -v } else { v });
+    }
+    let v = x.abs().pow(&Number::one().div(n, digits), digits)?;
+    let v = if x.is_negative() { -v } else { v };
+    // x^(1/n) is computed through a rounded exponent, so exact roots are checked separately
+    let rounded = v.round_to(precision, RoundingMode::HalfEven);
+    if n.is_integer() && rounded.pow(n, digits).as_ref() == Some(x) {
+        return Some(rounded);
+    }
+    Some(v)
+}
+
+/// hyperbolic sine, None on overflow
+pub fn sinh(x: &Number, digits: u64) -> Option<Number> {
+    let work = digits + 2;
+    let t = x.abs();
+    let v = if t < Number::one() {
+        // sinh x = x + x³/3! + x⁵/5! + ..., the exponentials would cancel each other
+        let t2 = &t * &t;
+        let mut term = t.clone();
+        let mut sum = t;
+        let mut n = 2;
+        loop {
+            term = (&term * &t2).div(&Number::from(n * (n + 1)), work);
+            if term.is_zero() || term.magnitude() < sum.magnitude() - work as i64 {
+                break;
+            }
+            sum = sum + &term;
+            n += 2;
+        }
+        sum
+    }
+    else {
+        let e = t.exp(work)?;
+        (&e - &Number::one().div(&e, work)).div(&Number::from(2), work)
+    };
+    let v = v.round_to(digits, RoundingMode::HalfEven);
+    Some(if x.is_negative() { -v } else { v })
+}
+
+/// hyperbolic cosine, None on overflow
+pub fn cosh(x: &Number, digits: u64) -> Option<Number> {
+    let work = digits + 2;
+    let e = x.abs().exp(work)?;
+    Some((&e + &Number::one().div(&e, work)).div(&Number::from(2), digits))
+}
+
+pub fn tanh(x: &Number, digits: u64) -> Number {
+    let work = digits + 2;
+    let t = x.abs();
+    let v = if t < Number::one() {
+        sinh(&t, work).unwrap().div(&cosh(&t, work).unwrap(), digits)
+    }
+    else {
+        // tanh x = 1 − 2 / (e^2x + 1), which is 1 at the working precision when e^2x is out of range
+        match (&t * &Number::from(2)).exp(work) {
+            Some(e) => Number::one() - Number::from(2).div(&(e + Number::one()), digits),
+            None => Number::one(),
+        }
+    };
+    if x.is_negative() { -v } else { v }
+}
+
+pub fn asinh(x: &Number, digits: u64) -> Number {
+    let work = digits + 2;
+    let t = x.abs();
+    let s = (&t * &t + Number::one()).sqrt(work);
+    let v = if t < Number::from_str("0.5").unwrap() {
+        // asinh x = atanh(x / √(x² + 1)), accurate near 0
+        t.div(&s, work).atanh(digits)
+    }
+    else {
+        (t + s).ln(digits)
+    };
+    if x.is_negative() { -v } else { v }
+}
+
+/// inverse hyperbolic cosine of a number from 1
+pub fn acosh(x: &Number, digits: u64) -> Number {
+    // acosh x = 2·asinh(√((x − 1) / 2)), accurate near 1 as well
+    let work = digits + 2;
+    let t = (x - &Number::one()).div(&Number::from(2), work).sqrt(work);
+    asinh(&t, work) * Number::from(2)
+}
+
+// nodes of the 15 point Gauss–Kronrod rule on [−1, 1] from the outside in, followed by 0,
+// the 2nd, 4th and 6th node and 0 are the nodes of the 7 point Gauss rule
+const KRONROD_NODES: [&str; 8] = [
+    "0.991455371120812639206854697526328517",
+    "0.949107912342758524526189684047851262",
+    "0.864864423359769072789712788640926201",
+    "0.741531185599394439863864773280788407",
+    "0.586087235467691130294144838258729598",
+    "0.405845151377397166906606412076961463",
+    "0.207784955007898467600689403773244913",
+    "0",
+];
+const KRONROD_WEIGHTS: [&str; 8] = [
+    "0.022935322010529224963732008058969592",
+    "0.063092092629978553290700663189204287",
+    "0.104790010322250183839876322541518017",
+    "0.140653259715525918745189590510237920",
+    "0.169004726639267902826583426598550284",
+    "0.190350578064785409913256402421013683",
+    "0.204432940075298892414161999234649085",
+    "0.209482141084727828012999174891714264",
+];
+const GAUSS_WEIGHTS: [&str; 4] = [
+    "0.129484966168869693270611432679082018",
+    "0.279705391489276667901467771423779582",
+    "0.381830050505118944950369775488975134",
+    "0.417959183673469387755102040816326531",
+];
+// intervals the range of an integral may be split into
+const MAX_INTERVALS: usize = 200;
+
+// part of the range of an integral with the Kronrod estimate of its integral and the error of the estimate
+struct Interval {
+    a: Number,
+    b: Number,
+    value: Number,
+    error: Number,
+    // integral of the absolute value, the scale of the error
+    magnitude: Number,
+}
+
+fn gauss_kronrod<F>(f: &F, a: Number, b: Number, digits: u64) -> Result<Interval, CalcError>
+where F: Fn(&Number) -> Result<Number, CalcError> {
+    let round = |v: Number| v.round_to(digits, RoundingMode::HalfEven);
+    let center = (&a + &b).div(&Number::from(2), digits);
+    let half = (&b - &a).div(&Number::from(2), digits);
+
+    let (mut kronrod, mut gauss, mut magnitude) = (Number::zero(), Number::zero(), Number::zero());
+    for (i, node) in KRONROD_NODES.iter().enumerate() {
+        let offset = round(&half * &Number::from_str(node).unwrap());
+        // the middle node is taken once, the others on both sides of the center
+        let values = if i == KRONROD_NODES.len() - 1 {
+            vec![f(&center)?]
+        }
+        else {
+            vec![f(&(&center - &offset))?, f(&(&center + &offset))?]
+        };
+        let sum = values.iter().fold(Number::zero(), |sum, v| sum + v);
+        let abs_sum = values.iter().fold(Number::zero(), |sum, v| sum + v.abs());
+        let weight = Number::from_str(KRONROD_WEIGHTS[i]).unwrap();
+        kronrod = round(kronrod + &weight * &sum);
+        magnitude = round(magnitude + &weight * &abs_sum);
+        if i % 2 == 1 {
+            gauss = round(gauss + Number::from_str(GAUSS_WEIGHTS[i / 2]).unwrap() * sum);
+        }
+    }
+    let value = round(&kronrod * &half);
+    let error = round((kronrod - gauss) * &half).abs();
+    let magnitude = round(magnitude * half.abs());
+    Ok(Interval { a, b, value, error, magnitude })
+}
+
+/// integral of f from a to b by adaptive Gauss–Kronrod quadrature, and an estimate of its error,
+/// the interval with the largest error is halved until the error is within the tolerance relative to
+/// the integral of |f|, the integral is rounded to the digits the error leaves, None if the tolerance is not reached
+pub fn integrate<F>(f: F, a: &Number, b: &Number, tolerance: &Number, digits: u64) -> Result<Option<(Number, Number)>, CalcError>
+where F: Fn(&Number) -> Result<Number, CalcError> {
+    let mut intervals = vec![gauss_kronrod(&f, a.clone(), b.clone(), digits)?];
+    loop {
+        let sum = |part: fn(&Interval) -> &Number| intervals.iter().fold(Number::zero(), |sum, interval| sum + part(interval));
+        let (value, error) = (sum(|interval| &interval.value), sum(|interval| &interval.error));
+        if error <= tolerance * &sum(|interval| &interval.magnitude) {
+            if error.is_zero() {
+                return Ok(Some((value, error)));
+            }
+            // the digits below the error are noise
+            let significant = value.magnitude() - error.magnitude();
+            let value = if significant < 0 { Number::zero() } else { value.round_to(significant as u64 + 1, RoundingMode::HalfEven) };
+            return Ok(Some((value, error)));
+        }
+        if intervals.len() >= MAX_INTERVALS {
+            return Ok(None);
+        }
+
+        let (i, _) = intervals.iter().enumerate().max_by(|x, y| x.1.error.cmp(&y.1.error)).unwrap();
+        let interval = intervals.swap_remove(i);
+        let middle = (&interval.a + &interval.b).div(&Number::from(2), digits);
+        intervals.push(gauss_kronrod(&f, interval.a, middle.clone(), digits)?);
+        intervals.push(gauss_kronrod(&f, middle, interval.b, digits)?);
+    }
+}
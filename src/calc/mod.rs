@@ -3,9 +3,26 @@ mod calculator;
 mod functions;
 mod context;
 mod expression;
+mod parser;
+mod math;
+mod error;
+mod number;
+mod complex;
+mod format;
+mod simplify;
+mod derivative;
 
 pub use self::calculator::*;
 pub use self::expression::*;
+pub use self::context::AngleMode;
+pub use self::context::ComplexFormat;
+pub use self::context::ImplicitMulPrecedence;
+pub use self::context::IntegerMode;
+pub use self::context::Radix;
+pub use self::context::RoundingMode;
+pub use self::error::CalcError;
+pub use self::format::{DisplayMode, NumberFormat, Separators};
+pub use self::number::Number;
 
 
 // mod calc {
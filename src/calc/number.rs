@@ -0,0 +1,1161 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::num::NonZeroU64;
+use std::str::FromStr;
+
+use bigdecimal::num_bigint::{BigInt, Sign};
+use bigdecimal::num_traits::Signed;
+use bigdecimal::{BigDecimal, ParseBigDecimalError, ToPrimitive, Zero, One};
+use num_rational::BigRational;
+
+use super::context::{Context, RoundingMode};
+use super::error::CalcError;
+
+// results above 10^MAX_EXPONENT overflow, results below 10^-MAX_EXPONENT become 0
+pub const MAX_EXPONENT: i64 = 9999;
+// extra digits carried by the intermediate steps of a function
+const GUARD_DIGITS: u64 = 10;
+// e^x is out of range beyond this argument
+const EXP_LIMIT: i64 = 23100;
+
+thread_local! {
+    // π to the most digits computed so far
+    static PI_CACHE: RefCell<Option<(u64, Number)>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    Decimal(BigDecimal),
+    // exact value of the fraction mode, always in lowest terms
+    Fraction(BigRational),
+    // real and imaginary part of the complex mode, the imaginary part is never 0
+    Complex(Box<(Number, Number)>),
+    // ∞, -∞ or NaN of the IEEE passthrough, never a finite value
+    NonFinite(f64),
+}
+
+/// decimal number of arbitrary precision, exact fraction or complex number, the value of every expression unit
+#[derive(Clone, Debug)]
+pub struct Number {
+    value: Value,
+}
+
+fn big_rounding(mode: RoundingMode) -> bigdecimal::RoundingMode {
+    match mode {
+        RoundingMode::HalfEven => bigdecimal::RoundingMode::HalfEven,
+        RoundingMode::HalfUp => bigdecimal::RoundingMode::HalfUp,
+        RoundingMode::HalfDown => bigdecimal::RoundingMode::HalfDown,
+        RoundingMode::Up => bigdecimal::RoundingMode::Up,
+        RoundingMode::Down => bigdecimal::RoundingMode::Down,
+        RoundingMode::Ceiling => bigdecimal::RoundingMode::Ceiling,
+        RoundingMode::Floor => bigdecimal::RoundingMode::Floor,
+    }
+}
+
+fn ten_pow(n: u64) -> BigInt {
+    BigInt::from(10).pow(n as u32)
+}
+
+fn digit_count(n: &BigInt) -> u64 {
+    BigDecimal::new(n.clone(), 0).digits()
+}
+
+// sign, digits without trailing zeros and power of ten of the first digit
+fn decompose(v: BigDecimal) -> (bool, String, i64) {
+    let v = v.normalized();
+    if v.is_zero() {
+        return (false, "0".to_string(), 0);
+    }
+    let (n, scale) = v.as_bigint_and_exponent();
+    let digits = n.abs().to_string();
+    let exponent = digits.len() as i64 - 1 - scale;
+    (n.is_negative(), digits, exponent)
+}
+
+// exact fraction of a decimal
+fn to_ratio(v: &BigDecimal) -> BigRational {
+    let (digits, scale) = v.as_bigint_and_exponent();
+    if scale >= 0 {
+        BigRational::new(digits, ten_pow(scale as u64))
+    }
+    else {
+        BigRational::from_integer(digits * ten_pow(scale.unsigned_abs()))
+    }
+}
+
+fn parse_integer(s: &str) -> Option<BigInt> {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    BigInt::from_str(s).ok()
+}
+
+// integer with a base prefix, e.g. "0xFF", "0o17" or "0b101"
+fn parse_radix(s: &str) -> Option<BigInt> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let radix = match s.get(..2)? {
+        "0x" | "0X" => 16,
+        "0o" | "0O" => 8,
+        "0b" | "0B" => 2,
+        _ => return None,
+    };
+    let digits = &s[2..];
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    let n = BigInt::parse_bytes(digits.as_bytes(), radix)?;
+    Some(if negative { -n } else { n })
+}
+
+// "3+4i", "-2.5i" or "i"
+fn parse_complex(s: &str) -> Option<Number> {
+    let body = s.strip_suffix('i')?;
+    // the imaginary part starts at the last sign which is not the sign of the number or of an exponent
+    let split = body.char_indices().rev().find(|(i, c)| {
+        (*c == '+' || *c == '-') && *i > 0 && !body[..*i].ends_with(['e', 'E'])
+    });
+    let (re, im) = match split {
+        Some((i, _)) => (Number::from_str(&body[..i]).ok()?, &body[i..]),
+        None => (Number::zero(), body),
+    };
+    let im = match im.strip_prefix('+').unwrap_or(im) {
+        "" => Number::one(),
+        "-" => -Number::one(),
+        im => Number::from_str(im).ok()?,
+    };
+    if re.is_complex() || im.is_complex() {
+        return None;
+    }
+    Some(Number::complex(re, im))
+}
+
+// "7/12" or the mixed number "1 5/12"
+fn parse_fraction(s: &str) -> Option<BigRational> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let (whole, fraction) = match s.split_once(' ') {
+        Some((whole, fraction)) => (parse_integer(whole)?, fraction),
+        None => (BigInt::zero(), s),
+    };
+    let (numer, denom) = fraction.split_once('/')?;
+    let (numer, denom) = (parse_integer(numer)?, parse_integer(denom)?);
+    if denom.is_zero() {
+        return None;
+    }
+    let v = BigRational::from_integer(whole) + BigRational::new(numer, denom);
+    Some(if negative { -v } else { v })
+}
+
+impl Number {
+    fn new(value: BigDecimal) -> Self {
+        Self { value: Value::Decimal(value) }
+    }
+
+    fn fraction(value: BigRational) -> Self {
+        Self { value: Value::Fraction(value) }
+    }
+
+    /// complex number, it is a real number when the imaginary part is 0
+    pub fn complex(re: Number, im: Number) -> Self {
+        if im.is_zero() {
+            return re;
+        }
+        Self { value: Value::Complex(Box::new((re, im))) }
+    }
+
+    /// ∞, -∞ or NaN, finite values become decimals
+    pub fn from_f64_value(v: f64) -> Self {
+        if v.is_finite() {
+            return Self::from_f64(v);
+        }
+        Self { value: Value::NonFinite(v) }
+    }
+
+    /// the imaginary unit i
+    pub fn i() -> Self {
+        Self::complex(Number::zero(), Number::one())
+    }
+
+    pub fn zero() -> Self {
+        Self::new(BigDecimal::zero())
+    }
+
+    pub fn one() -> Self {
+        Self::new(BigDecimal::one())
+    }
+
+    /// digits used by the functions to compute a result, a few more than the working precision
+    pub fn working_digits() -> u64 {
+        Context::with_current(|c| c.borrow().precision) + GUARD_DIGITS
+    }
+
+    pub fn is_fraction(&self) -> bool {
+        matches!(self.value, Value::Fraction(_))
+    }
+
+    pub fn is_complex(&self) -> bool {
+        matches!(self.value, Value::Complex(_))
+    }
+
+    pub fn is_finite(&self) -> bool {
+        !matches!(self.value, Value::NonFinite(_))
+    }
+
+    pub fn is_nan(&self) -> bool {
+        matches!(self.value, Value::NonFinite(v) if v.is_nan())
+    }
+
+    /// real part
+    pub fn re(&self) -> Number {
+        match &self.value {
+            Value::Complex(z) => z.0.clone(),
+            _ => self.clone(),
+        }
+    }
+
+    /// imaginary part
+    pub fn im(&self) -> Number {
+        match &self.value {
+            Value::Complex(z) => z.1.clone(),
+            _ => Number::zero(),
+        }
+    }
+
+    /// complex conjugate
+    pub fn conj(&self) -> Number {
+        match &self.value {
+            Value::Complex(z) => Self::complex(z.0.clone(), -&z.1),
+            _ => self.clone(),
+        }
+    }
+
+    // apply a function to the real and the imaginary part
+    fn map_parts<F>(&self, f: F) -> Number
+    where F: Fn(&Number) -> Number {
+        match &self.value {
+            Value::Complex(z) => Self::complex(f(&z.0), f(&z.1)),
+            _ => f(self),
+        }
+    }
+
+    /// exact fraction of the value
+    pub fn to_fraction(&self) -> Number {
+        match &self.value {
+            Value::Decimal(v) => Self::fraction(to_ratio(v)),
+            Value::Complex(_) => self.map_parts(|v| v.to_fraction()),
+            Value::Fraction(_) | Value::NonFinite(_) => self.clone(),
+        }
+    }
+
+    /// numerator and denominator of a fraction, as integers, None for the other values
+    pub fn fraction_parts(&self) -> Option<(Number, Number)> {
+        match &self.value {
+            Value::Fraction(v) => Some((Self::new(BigDecimal::new(v.numer().clone(), 0)), Self::new(BigDecimal::new(v.denom().clone(), 0)))),
+            _ => None,
+        }
+    }
+
+    /// decimal value, fractions are rounded to the given number of significant digits
+    pub fn to_decimal(&self, digits: u64) -> Number {
+        match &self.value {
+            Value::Decimal(_) | Value::NonFinite(_) => self.clone(),
+            Value::Fraction(v) => Self::new(BigDecimal::new(v.numer().clone(), 0)).div(&Self::new(BigDecimal::new(v.denom().clone(), 0)), digits),
+            Value::Complex(_) => self.map_parts(|v| v.to_decimal(digits)),
+        }
+    }
+
+    /// simplest fraction which agrees with the value to the given number of significant digits
+    pub fn to_approximate_fraction(&self, digits: u64) -> Number {
+        let x = match &self.value {
+            Value::Decimal(v) => to_ratio(v),
+            Value::Complex(_) => return self.map_parts(|v| v.to_approximate_fraction(digits)),
+            Value::Fraction(_) | Value::NonFinite(_) => return self.clone(),
+        };
+        let tolerance = x.abs() / BigRational::from_integer(ten_pow(digits));
+
+        // convergents of the continued fraction of x
+        let (mut p0, mut q0) = (BigInt::zero(), BigInt::one());
+        let (mut p1, mut q1) = (BigInt::one(), BigInt::zero());
+        let mut rest = x.clone();
+        loop {
+            let a = rest.floor().to_integer();
+            let (p2, q2) = (&a * &p1 + &p0, &a * &q1 + &q0);
+            let convergent = BigRational::new(p2.clone(), q2.clone());
+            let remainder = &rest - BigRational::from_integer(a);
+            if (&x - &convergent).abs() <= tolerance || remainder.is_zero() {
+                return Self::fraction(convergent);
+            }
+            rest = remainder.recip();
+            (p0, q0, p1, q1) = (p1, q1, p2, q2);
+        }
+    }
+
+    // both values as exact fractions, when the operation on them is exact: one of them is a fraction
+    // and the other is a fraction or an integer
+    fn exact_pair(&self, other: &Number) -> Option<(BigRational, BigRational)> {
+        match (&self.value, &other.value) {
+            (Value::Fraction(a), Value::Fraction(b)) => Some((a.clone(), b.clone())),
+            (Value::Fraction(a), Value::Decimal(b)) if b.is_integer() => Some((a.clone(), to_ratio(b))),
+            (Value::Decimal(a), Value::Fraction(b)) if a.is_integer() => Some((to_ratio(a), b.clone())),
+            _ => None,
+        }
+    }
+
+    // the value as a decimal, fractions are rounded to the working digits, complex numbers give their real part
+    fn big(&self) -> Cow<'_, BigDecimal> {
+        match &self.value {
+            Value::Decimal(v) => Cow::Borrowed(v),
+            Value::Fraction(_) => match self.to_decimal(Number::working_digits()).value {
+                Value::Decimal(v) => Cow::Owned(v),
+                _ => unreachable!(),
+            },
+            Value::Complex(z) => z.0.big(),
+            // non-finite values are kept away from the decimal arithmetic
+            Value::NonFinite(_) => Cow::Owned(BigDecimal::zero()),
+        }
+    }
+
+    // complex numbers and NaN have no sign
+    fn sign(&self) -> Option<Sign> {
+        match &self.value {
+            Value::Decimal(v) => Some(v.sign()),
+            Value::Fraction(v) => Some(v.numer().sign()),
+            Value::Complex(_) => None,
+            Value::NonFinite(v) if v.is_nan() => None,
+            Value::NonFinite(v) => Some(if *v < 0.0 { Sign::Minus } else { Sign::Plus }),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.sign() == Some(Sign::NoSign)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.sign() == Some(Sign::Minus)
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.sign() == Some(Sign::Plus)
+    }
+
+    pub fn is_integer(&self) -> bool {
+        match &self.value {
+            Value::Decimal(v) => v.is_integer(),
+            Value::Fraction(v) => v.is_integer(),
+            Value::Complex(_) | Value::NonFinite(_) => false,
+        }
+    }
+
+    /// absolute value, the modulus of a complex number
+    pub fn abs(&self) -> Number {
+        match &self.value {
+            Value::Decimal(v) => Self::new(v.abs()),
+            Value::Fraction(v) => Self::fraction(v.abs()),
+            Value::Complex(z) => (&z.0 * &z.0 + &z.1 * &z.1).sqrt(Number::working_digits()),
+            Value::NonFinite(v) => Self::from_f64_value(v.abs()),
+        }
+    }
+
+    /// the value as an integer, if it is one and fits
+    pub fn to_i64(&self) -> Option<i64> {
+        if !self.is_integer() {
+            return None;
+        }
+        match &self.value {
+            Value::Decimal(v) => v.to_i64(),
+            Value::Fraction(v) => v.numer().to_i64(),
+            Value::Complex(_) | Value::NonFinite(_) => None,
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        if let Value::NonFinite(v) = self.value {
+            return v;
+        }
+        // values beyond the range of a f64 are ±∞
+        let v = self.big().to_f64().unwrap_or(f64::NAN);
+        if v.is_nan() && self.magnitude() > 0 {
+            return if self.is_negative() { f64::NEG_INFINITY } else { f64::INFINITY };
+        }
+        v
+    }
+
+    /// power of ten of the leading digit, e.g. 2 for 123.4 and -2 for 0.05
+    pub fn magnitude(&self) -> i64 {
+        if self.is_zero() {
+            return 0;
+        }
+        match &self.value {
+            Value::Decimal(v) => v.order_of_magnitude(),
+            Value::Fraction(v) => {
+                // the magnitude is the difference of the digit counts, or one less
+                let (n, d) = (v.numer().abs(), v.denom());
+                let m = digit_count(&n) as i64 - digit_count(d) as i64;
+                let smaller = if m >= 0 { n < d * ten_pow(m as u64) } else { n * ten_pow(m.unsigned_abs()) < *d };
+                if smaller { m - 1 } else { m }
+            },
+            Value::Complex(z) => {
+                let re = if z.0.is_zero() { i64::MIN } else { z.0.magnitude() };
+                re.max(z.1.magnitude())
+            },
+            Value::NonFinite(_) => i64::MAX,
+        }
+    }
+
+    // approximate log10 of the absolute value, finite even when the value does not fit a f64
+    fn log10_estimate(&self) -> f64 {
+        let e = self.magnitude();
+        let (digits, scale) = self.big().as_bigint_and_exponent();
+        let mantissa = BigDecimal::new(digits, scale + e).to_f64().unwrap_or(1.0).abs();
+        e as f64 + mantissa.log10()
+    }
+
+    /// round to the given number of significant digits, fractions stay exact
+    pub fn round_to(&self, digits: u64, mode: RoundingMode) -> Number {
+        match &self.value {
+            Value::Decimal(v) if !v.is_zero() => {
+                Self::new(v.with_precision_round(NonZeroU64::new(digits.max(1)).unwrap(), big_rounding(mode)))
+            },
+            Value::Complex(_) => self.map_parts(|v| v.round_to(digits, mode)),
+            _ => self.clone(),
+        }
+    }
+
+    /// sign, digits and power of ten of the first digit of the real value rounded to the given number of
+    /// significant digits, trailing zeros are dropped, e.g. (false, "1235", 3) for 1234.5 to 4 digits
+    pub fn to_digits(&self, digits: u64, mode: RoundingMode) -> (bool, String, i64) {
+        decompose(self.big().with_precision_round(NonZeroU64::new(digits.max(1)).unwrap(), big_rounding(mode)))
+    }
+
+    /// the same as to_digits, for the real value rounded to the given number of decimals
+    pub fn to_fixed_digits(&self, decimals: i64, mode: RoundingMode) -> (bool, String, i64) {
+        decompose(self.big().with_scale_round(decimals, big_rounding(mode)))
+    }
+
+    /// intermediate rounding of the functions
+    pub fn round_digits(&self, digits: u64) -> Number {
+        self.round_to(digits, RoundingMode::HalfEven)
+    }
+
+    /// round a result to the working precision, fails when it is too large to be represented
+    pub fn round_result(self, exp_idx: i32) -> Result<Number, CalcError> {
+        let (precision, mode, fraction_mode, complex_mode, integer_mode, ieee_passthrough) = Context::with_current(|c| {
+            let c = c.borrow();
+            (c.precision, c.rounding_mode, c.fraction_mode, c.complex_mode, c.integer_mode, c.ieee_passthrough)
+        });
+        // ∞ and NaN are errors, unless the IEEE passthrough keeps them
+        if let Value::NonFinite(v) = self.value {
+            if ieee_passthrough && integer_mode.is_none() {
+                return Ok(self);
+            }
+            return Err(if v.is_nan() { CalcError::Domain { exp_idx } } else { CalcError::Overflow { exp_idx } });
+        }
+        // the programmer mode drops the fraction part and wraps around to the word size
+        if let Some(integer_mode) = integer_mode {
+            if self.is_complex() {
+                return Err(CalcError::Domain { exp_idx });
+            }
+            return Ok(self.trunc().wrap(integer_mode.bits, integer_mode.signed).unwrap());
+        }
+        // each part of a complex number is rounded as a real number
+        if let Value::Complex(z) = self.value {
+            if !complex_mode {
+                return Err(CalcError::Domain { exp_idx });
+            }
+            let (re, im) = *z;
+            let (mut re, mut im) = (re.round_result(exp_idx)?, im.round_result(exp_idx)?);
+            // a part which is below the precision of the other one is a rounding error
+            if !re.is_zero() && im.magnitude() < re.magnitude() - precision as i64 {
+                im = Number::zero();
+            }
+            if !im.is_zero() && re.magnitude() < im.magnitude() - precision as i64 {
+                re = Number::zero();
+            }
+            return Ok(Number::complex(re, im));
+        }
+        let mut v = self;
+        // out of the fraction mode, or with more digits than the working precision, a fraction becomes a decimal
+        if let Value::Fraction(f) = &v.value {
+            if !fraction_mode || digit_count(f.numer()) > precision || digit_count(f.denom()) > precision {
+                v = v.to_decimal(precision + GUARD_DIGITS);
+            }
+        }
+        let v = v.round_to(precision, mode);
+        if v.magnitude() > MAX_EXPONENT {
+            if ieee_passthrough {
+                return Ok(Number::from_f64_value(if v.is_negative() { f64::NEG_INFINITY } else { f64::INFINITY }));
+            }
+            return Err(CalcError::Overflow { exp_idx });
+        }
+        if v.magnitude() < -MAX_EXPONENT {
+            return Ok(Number::zero());
+        }
+        Ok(v)
+    }
+
+    /// integer part, rounded towards -∞
+    pub fn floor(&self) -> Number {
+        match &self.value {
+            Value::Decimal(v) => Self::new(v.with_scale_round(0, bigdecimal::RoundingMode::Floor)),
+            Value::Fraction(v) => Self::fraction(v.floor()),
+            Value::Complex(_) => self.map_parts(|v| v.floor()),
+            Value::NonFinite(_) => self.clone(),
+        }
+    }
+
+    /// nearest integer, halves are rounded away from 0
+    pub fn round(&self) -> Number {
+        match &self.value {
+            Value::Decimal(v) => Self::new(v.with_scale_round(0, bigdecimal::RoundingMode::HalfUp)),
+            Value::Fraction(v) => Self::fraction(v.round()),
+            Value::Complex(_) => self.map_parts(|v| v.round()),
+            Value::NonFinite(_) => self.clone(),
+        }
+    }
+
+    /// integer part, rounded towards 0
+    pub fn trunc(&self) -> Number {
+        match &self.value {
+            Value::Decimal(v) => Self::new(v.with_scale_round(0, bigdecimal::RoundingMode::Down)),
+            Value::Fraction(v) => Self::fraction(v.trunc()),
+            Value::Complex(_) => self.map_parts(|v| v.trunc()),
+            Value::NonFinite(_) => self.clone(),
+        }
+    }
+
+    // the value as an integer, if it is one
+    fn to_bigint(&self) -> Option<BigInt> {
+        match &self.value {
+            Value::Decimal(v) if v.is_integer() => Some(v.with_scale(0).as_bigint_and_exponent().0),
+            Value::Fraction(v) if v.is_integer() => Some(v.to_integer()),
+            _ => None,
+        }
+    }
+
+    fn from_bigint(n: BigInt) -> Number {
+        Self::new(BigDecimal::new(n, 0))
+    }
+
+    /// the integer wrapped around to a word of the given size, in two's complement when it is signed
+    pub fn wrap(&self, bits: u32, signed: bool) -> Option<Number> {
+        let modulus = BigInt::one() << bits;
+        let mut n = ((self.to_bigint()? % &modulus) + &modulus) % &modulus;
+        if signed && n >= (&modulus >> 1u32) {
+            n -= &modulus;
+        }
+        Some(Self::from_bigint(n))
+    }
+
+    /// bitwise and of two integers, negative integers are taken in two's complement
+    pub fn bit_and(&self, other: &Number) -> Option<Number> {
+        Some(Self::from_bigint(self.to_bigint()? & other.to_bigint()?))
+    }
+
+    /// bitwise or of two integers
+    pub fn bit_or(&self, other: &Number) -> Option<Number> {
+        Some(Self::from_bigint(self.to_bigint()? | other.to_bigint()?))
+    }
+
+    /// bitwise exclusive or of two integers
+    pub fn bit_xor(&self, other: &Number) -> Option<Number> {
+        Some(Self::from_bigint(self.to_bigint()? ^ other.to_bigint()?))
+    }
+
+    /// bitwise complement of an integer, -n-1
+    pub fn bit_not(&self) -> Option<Number> {
+        Some(Self::from_bigint(!self.to_bigint()?))
+    }
+
+    /// the integer shifted left by n bits, or right when n is negative, right shifts round towards -∞
+    pub fn shift(&self, n: i64) -> Option<Number> {
+        let x = self.to_bigint()?;
+        let x = if n >= 0 { x << n.unsigned_abs() } else { x >> n.unsigned_abs() };
+        Some(Self::from_bigint(x))
+    }
+
+    /// the integer rotated left by n bits inside a word of the given size, or right when n is negative
+    pub fn rotate(&self, n: i64, bits: u32) -> Option<Number> {
+        let modulus = BigInt::one() << bits;
+        let x = ((self.to_bigint()? % &modulus) + &modulus) % &modulus;
+        let n = n.rem_euclid(bits as i64) as u32;
+        Some(Self::from_bigint(((&x << n) | (&x >> (bits - n))) % &modulus))
+    }
+
+    /// digits of the integer in the given base, a negative integer is written as its two's complement in
+    /// a word of the given size in the other bases than 10
+    pub fn to_radix_string(&self, radix: u32, bits: u32) -> Option<String> {
+        let mut n = self.to_bigint()?;
+        if radix != 10 && n.is_negative() {
+            n += BigInt::one() << bits;
+        }
+        Some(n.to_str_radix(radix).to_uppercase())
+    }
+
+    /// exact remainder of a truncated division, it has the sign of the dividend
+    pub fn rem(&self, other: &Number) -> Number {
+        if let Some((a, b)) = self.exact_pair(other) {
+            let q = (&a / &b).trunc();
+            return Self::fraction(a - q * b);
+        }
+        Self::new(&*self.big() % &*other.big())
+    }
+
+    /// quotient to the given number of significant digits, the divisor must not be 0
+    pub fn div(&self, other: &Number, digits: u64) -> Number {
+        if self.is_zero() {
+            return Number::zero();
+        }
+        if self.is_complex() || other.is_complex() {
+            // (a+bi)/(c+di) = ((ac+bd) + (bc−ad)i) / (c²+d²)
+            let (a, b, c, d) = (self.re(), self.im(), other.re(), other.im());
+            let work = digits + GUARD_DIGITS;
+            let denom = (&c * &c + &d * &d).round_digits(work);
+            let re = (&a * &c + &b * &d).round_digits(work).div(&denom, digits);
+            let im = (&b * &c - &a * &d).round_digits(work).div(&denom, digits);
+            return Self::complex(re, im);
+        }
+        if let Some((a, b)) = self.exact_pair(other) {
+            return Self::fraction(a / b);
+        }
+        let (a, a_scale) = self.big().as_bigint_and_exponent();
+        let (b, b_scale) = other.big().as_bigint_and_exponent();
+
+        // shift the dividend so that the integer quotient has at least digits + 1 digits
+        let shift = (digits as i64 + 1 + digit_count(&b) as i64 - digit_count(&a) as i64).max(0) as u64;
+        let a = a * ten_pow(shift);
+        let mut q = &a / &b;
+        let mut scale = a_scale - b_scale + shift as i64;
+        // a non-zero remainder adds a sticky digit, so that the quotient is rounded as if it was exact
+        if !(&a % &b).is_zero() {
+            let sticky = if (a.sign() == Sign::Minus) != (b.sign() == Sign::Minus) { -1 } else { 1 };
+            q = q * 10 + sticky;
+            scale += 1;
+        }
+        Self::new(BigDecimal::new(q, scale)).round_digits(digits)
+    }
+
+    /// floor of the quotient, the divisor must not be 0
+    pub fn div_floor(&self, other: &Number) -> Number {
+        if let Some((a, b)) = self.exact_pair(other) {
+            return Self::fraction((a / b).floor());
+        }
+        let (a, a_scale) = self.big().as_bigint_and_exponent();
+        let (b, b_scale) = other.big().as_bigint_and_exponent();
+
+        // bring both numbers to the same scale, then the quotient of the digits is the result
+        let scale = a_scale.max(b_scale);
+        let a = a * ten_pow((scale - a_scale) as u64);
+        let b = b * ten_pow((scale - b_scale) as u64);
+        let mut q = &a / &b;
+        if !(&a % &b).is_zero() && (a.sign() == Sign::Minus) != (b.sign() == Sign::Minus) {
+            q -= 1;
+        }
+        Self::new(BigDecimal::new(q, 0))
+    }
+
+    pub fn sqrt(&self, digits: u64) -> Number {
+        if self.is_zero() {
+            return Number::zero();
+        }
+        if let Value::Fraction(v) = &self.value {
+            // the root of a fraction of two squares stays exact
+            let (n, d) = (v.numer().sqrt(), v.denom().sqrt());
+            if &n * &n == *v.numer() && &d * &d == *v.denom() {
+                return Self::fraction(BigRational::new(n, d));
+            }
+            return self.to_decimal(digits + GUARD_DIGITS).sqrt(digits);
+        }
+        let ctx = bigdecimal::Context::new(NonZeroU64::new(digits).unwrap(), bigdecimal::RoundingMode::HalfEven);
+        Self::new(self.big().sqrt_with_context(&ctx).unwrap_or_default())
+    }
+
+    /// π to the given number of significant digits
+    pub fn pi(digits: u64) -> Number {
+        let cached = PI_CACHE.with(|c| {
+            c.borrow().as_ref().filter(|(d, _)| *d >= digits).map(|(_, pi)| pi.round_digits(digits))
+        });
+        if let Some(pi) = cached {
+            return pi;
+        }
+
+        // Machin's formula π = 16·atan(1/5) − 4·atan(1/239), summed in fixed point
+        let scale = digits + GUARD_DIGITS;
+        let one = ten_pow(scale);
+        let atan_inv = |n: u32| {
+            let n2 = BigInt::from(n * n);
+            let mut term = &one / n;
+            let mut sum = BigInt::zero();
+            let mut k: u32 = 0;
+            while !term.is_zero() {
+                if k.is_multiple_of(2) {
+                    sum += &term / (2 * k + 1);
+                }
+                else {
+                    sum -= &term / (2 * k + 1);
+                }
+                term /= &n2;
+                k += 1;
+            }
+            sum
+        };
+        let pi = atan_inv(5) * 16 - atan_inv(239) * 4;
+        let pi = Self::new(BigDecimal::new(pi, scale as i64)).round_digits(digits);
+        PI_CACHE.with(|c| c.replace(Some((digits, pi.clone()))));
+        pi
+    }
+
+    /// e^x, None when the result is out of range
+    pub fn exp(&self, digits: u64) -> Option<Number> {
+        if self.is_fraction() {
+            return self.to_decimal(digits + GUARD_DIGITS).exp(digits);
+        }
+        if self.is_zero() {
+            return Some(Number::one());
+        }
+        if *self > Number::from(EXP_LIMIT) {
+            return None;
+        }
+        if *self < Number::from(-EXP_LIMIT) {
+            return Some(Number::zero());
+        }
+
+        // e^x = (e^(x/2^k))^(2^k), the argument is halved until the series converges quickly
+        let k = (self.magnitude() + 1).max(0) as u64 * 10 / 3 + 10;
+        let work = digits + k / 3 + 2;
+        let r = self.div(&Number::from(2).powi(k as i64, work), work);
+
+        let mut sum = Number::one();
+        let mut term = Number::one();
+        let mut n = 1;
+        loop {
+            term = (&term * &r).div(&Number::from(n), work);
+            if term.is_zero() || term.magnitude() < sum.magnitude() - work as i64 {
+                break;
+            }
+            sum = sum + &term;
+            n += 1;
+        }
+        for _ in 0..k {
+            sum = (&sum * &sum).round_digits(work);
+        }
+        Some(sum.round_digits(digits))
+    }
+
+    /// natural logarithm of a positive number
+    pub fn ln(&self, digits: u64) -> Number {
+        if self.is_fraction() {
+            return self.to_decimal(digits + GUARD_DIGITS).ln(digits);
+        }
+        if *self == Number::one() {
+            return Number::zero();
+        }
+        // near 1 the series of atanh keeps the small result accurate
+        let d = self - &Number::one();
+        if d.abs() < Number::from_str("0.1").unwrap() {
+            let s = d.div(&(self + &Number::one()), digits + 2);
+            return (s.atanh_series(digits + 2) * Number::from(2)).round_digits(digits);
+        }
+
+        // x = m·10^e with 1 ≤ m < 10, ln x = ln m + e·ln 10
+        let e = self.magnitude();
+        let (int_val, scale) = self.big().as_bigint_and_exponent();
+        let m = Self::new(BigDecimal::new(int_val, scale + e));
+        let work = digits + 2 + e.unsigned_abs().to_string().len() as u64;
+        let mut v = m.ln_newton(work);
+        if e != 0 {
+            v = v + Number::from(10).ln_newton(work) * Number::from(e);
+        }
+        v.round_digits(digits)
+    }
+
+    // ln of a number between 1 and 10 by Halley's iteration y += 2(x − e^y)/(x + e^y)
+    fn ln_newton(&self, digits: u64) -> Number {
+        let work = digits + 5;
+        let mut y = Number::from_f64(self.to_f64().ln());
+        // the number of correct digits triples with every step, the last step confirms the result
+        let mut correct = 14;
+        loop {
+            let done = correct >= work;
+            let t = y.exp(work).unwrap();
+            y = y + (&(self - &t) * &Number::from(2)).div(&(self + &t), work);
+            if done {
+                return y.round_digits(digits);
+            }
+            correct *= 3;
+        }
+    }
+
+    // atanh(x) = x + x³/3 + x⁵/5 + ..., for small x
+    fn atanh_series(&self, digits: u64) -> Number {
+        let x2 = (self * self).round_digits(digits);
+        let mut power = self.clone();
+        let mut sum = self.clone();
+        let mut n = 3;
+        loop {
+            power = (&power * &x2).round_digits(digits);
+            let term = power.div(&Number::from(n), digits);
+            if term.is_zero() || term.magnitude() < sum.magnitude() - digits as i64 {
+                break;
+            }
+            sum = sum + term;
+            n += 2;
+        }
+        sum.round_digits(digits)
+    }
+
+    /// inverse hyperbolic tangent of a number between -1 and 1
+    pub fn atanh(&self, digits: u64) -> Number {
+        if self.is_fraction() {
+            return self.to_decimal(digits + GUARD_DIGITS).atanh(digits);
+        }
+        if self.abs() < Number::from_str("0.5").unwrap() {
+            return self.atanh_series(digits + 2).round_digits(digits);
+        }
+        let work = digits + GUARD_DIGITS;
+        let q = (Number::one() + self).div(&(Number::one() - self), work);
+        q.ln(work).div(&Number::from(2), digits)
+    }
+
+    /// x^y, None when the result is out of range, a negative base needs an integer exponent
+    pub fn pow(&self, y: &Number, digits: u64) -> Option<Number> {
+        if y.is_zero() {
+            return Some(Number::one());
+        }
+        if self.is_zero() {
+            return Some(Number::zero());
+        }
+
+        // estimate the result, so that huge powers are not computed
+        let log10 = self.log10_estimate() * y.to_f64();
+        if log10 > (MAX_EXPONENT + 1) as f64 {
+            return None;
+        }
+        if log10 < -(MAX_EXPONENT + 1) as f64 {
+            return Some(Number::zero());
+        }
+
+        if let Value::Fraction(v) = &self.value {
+            // a power of a fraction stays exact while it has a moderate number of digits
+            let size = digit_count(v.numer()).max(digit_count(v.denom()));
+            match y.to_i64() {
+                Some(n) if n.unsigned_abs().saturating_mul(size) <= 10 * digits => return Some(self.powi(n, digits)),
+                _ => return self.to_decimal(digits + GUARD_DIGITS).pow(y, digits),
+            }
+        }
+        if let Some(n) = y.to_i64() {
+            if n.abs() <= 1_000_000 {
+                return Some(self.powi(n, digits));
+            }
+        }
+        let v = (y * &self.abs().ln(digits + log10.abs().log10().max(0.0) as u64 + 2)).exp(digits)?;
+        if self.is_negative() && !y.rem(&Number::from(2)).is_zero() {
+            return Some(-v);
+        }
+        Some(v)
+    }
+
+    // integer power by repeated squaring
+    fn powi(&self, n: i64, digits: u64) -> Number {
+        let work = digits + n.unsigned_abs().to_string().len() as u64 + 2;
+        let mut result = Number::one();
+        let mut base = self.clone();
+        let mut e = n.unsigned_abs();
+        while e > 0 {
+            if e & 1 == 1 {
+                result = (&result * &base).round_digits(work);
+            }
+            e >>= 1;
+            if e > 0 {
+                base = (&base * &base).round_digits(work);
+            }
+        }
+        if n < 0 {
+            return Number::one().div(&result, digits);
+        }
+        result.round_digits(digits)
+    }
+
+    /// sine of an angle in radians
+    pub fn sin(&self, digits: u64) -> Number {
+        if self.is_fraction() {
+            return self.to_decimal(digits + GUARD_DIGITS).sin(digits);
+        }
+        if self.is_zero() {
+            return Number::zero();
+        }
+        let work = digits + GUARD_DIGITS + self.magnitude().max(0) as u64;
+        let pi = Number::pi(work);
+        Self::sin_reduced(self, &pi, digits)
+    }
+
+    /// cosine of an angle in radians
+    pub fn cos(&self, digits: u64) -> Number {
+        if self.is_fraction() {
+            return self.to_decimal(digits + GUARD_DIGITS).cos(digits);
+        }
+        // cos x = sin(π/2 − x)
+        let work = digits + GUARD_DIGITS + self.magnitude().max(0) as u64;
+        let pi = Number::pi(work);
+        let x = pi.div(&Number::from(2), work) - self;
+        Self::sin_reduced(&x, &pi, digits)
+    }
+
+    fn sin_reduced(x: &Number, pi: &Number, digits: u64) -> Number {
+        let work = digits + GUARD_DIGITS;
+        // bring the angle to [-π/2, π/2], where sin(π − x) = sin x
+        let two_pi = pi * &Number::from(2);
+        let half_pi = pi.div(&Number::from(2), work + 2);
+        let n = x.div(&two_pi, x.magnitude().max(0) as u64 + 2).round();
+        let mut r = x - &(&n * &two_pi);
+        if r > half_pi {
+            r = pi - &r;
+        }
+        else if r < -&half_pi {
+            r = -pi - &r;
+        }
+        if r.is_zero() {
+            return Number::zero();
+        }
+
+        // sin x = x − x³/3! + x⁵/5! − ...
+        let r2 = (&r * &r).round_digits(work);
+        let mut term = r.clone();
+        let mut sum = r;
+        let mut n = 2;
+        loop {
+            term = -(&term * &r2).div(&Number::from(n * (n + 1)), work);
+            if term.is_zero() || term.magnitude() < sum.magnitude() - work as i64 {
+                break;
+            }
+            sum = sum + &term;
+            n += 2;
+        }
+        sum.round_digits(digits)
+    }
+
+    /// inverse tangent in radians
+    pub fn atan(&self, digits: u64) -> Number {
+        if self.is_fraction() {
+            return self.to_decimal(digits + GUARD_DIGITS).atan(digits);
+        }
+        if self.is_zero() {
+            return Number::zero();
+        }
+        let work = digits + GUARD_DIGITS;
+        let mut x = self.abs();
+        // atan x = π/2 − atan(1/x)
+        let inverted = x > Number::one();
+        if inverted {
+            x = Number::one().div(&x, work);
+        }
+        // atan x = 2·atan(x / (1 + √(1 + x²))), until the series converges quickly
+        let mut halvings: i64 = 0;
+        let small = Number::from_str("0.1").unwrap();
+        while x > small {
+            let d = Number::one() + (Number::one() + &x * &x).sqrt(work);
+            x = x.div(&d, work);
+            halvings += 1;
+        }
+
+        // atan x = x − x³/3 + x⁵/5 − ...
+        let x2 = (&x * &x).round_digits(work);
+        let mut power = x.clone();
+        let mut sum = x;
+        let mut n = 3;
+        loop {
+            power = -(&power * &x2).round_digits(work);
+            let term = power.div(&Number::from(n), work);
+            if term.is_zero() || term.magnitude() < sum.magnitude() - work as i64 {
+                break;
+            }
+            sum = sum + term;
+            n += 2;
+        }
+        let mut v = sum * Number::from(2).powi(halvings, work);
+        if inverted {
+            v = Number::pi(work).div(&Number::from(2), work) - v;
+        }
+        if self.is_negative() {
+            v = -v;
+        }
+        v.round_digits(digits)
+    }
+
+    pub fn from_f64(v: f64) -> Number {
+        Self::new(BigDecimal::try_from(v).unwrap_or_default())
+    }
+
+    /// fraction as a mixed number such as "1 5/12", other values as they are displayed
+    pub fn to_mixed_string(&self) -> String {
+        match &self.value {
+            Value::Fraction(v) if !v.is_integer() && v.numer().abs() > *v.denom() => {
+                let whole = v.trunc();
+                format!("{} {}", whole.numer(), (v - &whole).abs())
+            },
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl From<i64> for Number {
+    fn from(v: i64) -> Self {
+        Self::new(BigDecimal::from(v))
+    }
+}
+
+impl FromStr for Number {
+    type Err = ParseBigDecimalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "∞" => return Ok(Self::from_f64_value(f64::INFINITY)),
+            "-∞" => return Ok(Self::from_f64_value(f64::NEG_INFINITY)),
+            "NaN" => return Ok(Self::from_f64_value(f64::NAN)),
+            _ => {},
+        }
+        if s.ends_with('i') {
+            return parse_complex(s).ok_or(ParseBigDecimalError::Other(format!("invalid complex number {}", s)));
+        }
+        if let Some(n) = parse_radix(s) {
+            return Ok(Self::from_bigint(n));
+        }
+        if s.contains('/') {
+            return parse_fraction(s).map(Self::fraction).ok_or(ParseBigDecimalError::Other(format!("invalid fraction {}", s)));
+        }
+        BigDecimal::from_str(s).map(Self::new)
+    }
+}
+
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.value {
+            Value::Decimal(v) => write!(f, "{}", v.normalized().to_plain_string()),
+            Value::Fraction(v) => write!(f, "{}", v),
+            Value::Complex(z) => {
+                if !z.0.is_zero() {
+                    write!(f, "{}", z.0)?;
+                    if !z.1.is_negative() {
+                        write!(f, "+")?;
+                    }
+                }
+                if z.1 == -Number::one() {
+                    write!(f, "-i")
+                }
+                else if z.1 == Number::one() {
+                    write!(f, "i")
+                }
+                else {
+                    write!(f, "{}i", z.1)
+                }
+            },
+            Value::NonFinite(v) if v.is_nan() => write!(f, "NaN"),
+            Value::NonFinite(v) => write!(f, "{}∞", if *v < 0.0 { "-" } else { "" }),
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Number) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Number) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Number) -> Ordering {
+        match (&self.value, &other.value) {
+            (Value::Decimal(a), Value::Decimal(b)) => a.cmp(b),
+            (Value::Fraction(a), Value::Fraction(b)) => a.cmp(b),
+            (Value::Decimal(a), Value::Fraction(b)) => to_ratio(a).cmp(b),
+            (Value::Fraction(a), Value::Decimal(b)) => a.cmp(&to_ratio(b)),
+            // -∞ and ∞ are below and above every number, NaN is above ∞
+            (Value::NonFinite(_), _) | (_, Value::NonFinite(_)) => self.to_f64().total_cmp(&other.to_f64()),
+            // complex numbers are not ordered, they are sorted by their real and then imaginary part
+            _ => (self.re(), self.im()).cmp(&(other.re(), other.im())),
+        }
+    }
+}
+
+macro_rules! impl_number_op {
+    ($op:ident, $method:ident, $complex:expr) => {
+        impl std::ops::$op<&Number> for &Number {
+            type Output = Number;
+            fn $method(self, other: &Number) -> Number {
+                // with ∞ or NaN, the result is the one of floating point numbers
+                if !self.is_finite() || !other.is_finite() {
+                    return Number::from_f64_value(std::ops::$op::$method(self.to_f64(), other.to_f64()));
+                }
+                if self.is_complex() || other.is_complex() {
+                    return $complex(self, other);
+                }
+                match self.exact_pair(other) {
+                    Some((a, b)) => Number::fraction(std::ops::$op::$method(a, b)),
+                    None => Number::new(std::ops::$op::$method(&*self.big(), &*other.big())),
+                }
+            }
+        }
+
+        impl std::ops::$op<Number> for Number {
+            type Output = Number;
+            fn $method(self, other: Number) -> Number {
+                std::ops::$op::$method(&self, &other)
+            }
+        }
+
+        impl std::ops::$op<&Number> for Number {
+            type Output = Number;
+            fn $method(self, other: &Number) -> Number {
+                std::ops::$op::$method(&self, other)
+            }
+        }
+
+        impl std::ops::$op<Number> for &Number {
+            type Output = Number;
+            fn $method(self, other: Number) -> Number {
+                std::ops::$op::$method(self, &other)
+            }
+        }
+    };
+}
+
+impl_number_op!(Add, add, |a: &Number, b: &Number| Number::complex(a.re() + b.re(), a.im() + b.im()));
+impl_number_op!(Sub, sub, |a: &Number, b: &Number| Number::complex(a.re() - b.re(), a.im() - b.im()));
+impl_number_op!(Mul, mul, complex_mul);
+
+// (a+bi)(c+di) = (ac−bd) + (ad+bc)i
+fn complex_mul(x: &Number, y: &Number) -> Number {
+    let (a, b, c, d) = (x.re(), x.im(), y.re(), y.im());
+    Number::complex(&a * &c - &b * &d, &a * &d + &b * &c)
+}
+
+impl std::ops::Neg for Number {
+    type Output = Number;
+    fn neg(self) -> Number {
+        -&self
+    }
+}
+
+impl std::ops::Neg for &Number {
+    type Output = Number;
+    fn neg(self) -> Number {
+        match &self.value {
+            Value::Decimal(v) => Number::new(-v),
+            Value::Fraction(v) => Number::fraction(-v),
+            Value::Complex(z) => Number::complex(-&z.0, -&z.1),
+            Value::NonFinite(v) => Number::from_f64_value(-v),
+        }
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+
+use super::context::Context;
+use super::error::CalcError;
+use super::expression::*;
+
+pub enum TokenKind {
+    Number,
+    Identifier,
+    Functor,
+    Unknown,
+}
+
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub pos: usize,
+}
+
+// ascii spellings of the operators registered in the function library
+const ASCII_ALIASES: [(&str, &str); 3] = [
+    ("*", EXP_UNIT_NAME_MUL),
+    ("/", EXP_UNIT_NAME_DIV),
+    ("-", EXP_UNIT_NAME_SUB),
+];
+
+// longest functor name which is written with symbols
+const MAX_SYMBOL_LEN: usize = 3;
+
+/// split a text expression into number, identifier and functor tokens
+pub struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Lexer {
+    pub fn new(input: &str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    pub fn is_identifier(name: &str) -> bool {
+        let mut chars = name.chars();
+        let first = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_');
+        first && chars.all(|c| c.is_alphanumeric() || c == '_')
+    }
+
+    // functor names which are not plain identifiers (+, √, ², etc.) are matched greedily
+    fn is_symbolic(name: &str) -> bool {
+        !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    fn match_symbol(&self, start: usize) -> Option<(usize, String)> {
+        let max_len = MAX_SYMBOL_LEN.min(self.chars.len() - start);
+        for len in (1..=max_len).rev() {
+            let candidate: String = self.chars[start..start + len].iter().collect();
+            if candidate == EXP_UNIT_NAME_CLOSE_BRK || candidate == EXP_UNIT_NAME_COMMA {
+                return Some((len, candidate));
+            }
+            if Self::is_symbolic(&candidate) && ExpressionBuilder::is_functor(&candidate) {
+                return Some((len, candidate));
+            }
+            let alias = ASCII_ALIASES.iter().find(|(ascii, _)| *ascii == candidate);
+            if let Some((_, name)) = alias {
+                return Some((len, name.to_string()));
+            }
+        }
+        None
+    }
+
+    // length of the fraction part " 5/12" of a mixed number which starts at the given position
+    fn match_mixed_fraction(&self, start: usize) -> Option<usize> {
+        let digits_at = |i: usize| self.chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+        let mut i = start;
+        let spaces = self.chars[i..].iter().take_while(|c| **c == ' ').count();
+        if spaces == 0 {
+            return None;
+        }
+        i += spaces;
+        let numer = digits_at(i);
+        if numer == 0 || self.chars.get(i + numer) != Some(&'/') {
+            return None;
+        }
+        i += numer + 1;
+        let denom = digits_at(i);
+        if denom == 0 {
+            return None;
+        }
+        Some(i + denom - start)
+    }
+
+    // length of an integer with a base prefix which starts at the given position, e.g. "0xFF"
+    fn match_radix_integer(&self, start: usize) -> Option<usize> {
+        if self.chars[start] != '0' {
+            return None;
+        }
+        let radix = match self.chars.get(start + 1) {
+            Some('x') | Some('X') => 16,
+            Some('o') | Some('O') => 8,
+            Some('b') | Some('B') => 2,
+            _ => return None,
+        };
+        let digits = self.chars[start + 2..].iter().take_while(|c| c.is_digit(radix)).count();
+        if digits == 0 {
+            return None;
+        }
+        Some(2 + digits)
+    }
+
+    // exponent which starts at the given position, e.g. "e23" or "e−3", with the sign written as '-'
+    fn match_exponent(&self, start: usize) -> Option<(usize, String)> {
+        if !matches!(self.chars.get(start), Some('e') | Some('E')) {
+            return None;
+        }
+        let mut exponent = "e".to_string();
+        let mut i = start + 1;
+        match self.chars.get(i) {
+            Some('+') => i += 1,
+            Some('-') | Some('−') => {
+                exponent.push('-');
+                i += 1;
+            },
+            _ => {},
+        }
+        let digits: String = self.chars[i..].iter().take_while(|c| c.is_ascii_digit()).collect();
+        if digits.is_empty() {
+            return None;
+        }
+        exponent.push_str(&digits);
+        Some((i + digits.len() - start, exponent))
+    }
+
+    fn take_while<F>(&mut self, f: F) -> String
+    where F: Fn(&Lexer, usize) -> bool {
+        let mut text = String::new();
+        while self.pos < self.chars.len() && f(self, self.pos) {
+            text.push(self.chars[self.pos]);
+            self.pos += 1;
+        }
+        text
+    }
+}
+
+impl Iterator for Lexer {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+        if self.pos >= self.chars.len() {
+            return None;
+        }
+
+        let pos = self.pos;
+        if let Some((len, name)) = self.match_symbol(pos) {
+            self.pos += len;
+            return Some(Token { kind: TokenKind::Functor, text: name, pos });
+        }
+
+        if let Some(len) = self.match_radix_integer(pos) {
+            let text: String = self.chars[pos..pos + len].iter().collect();
+            self.pos += len;
+            return Some(Token { kind: TokenKind::Number, text, pos });
+        }
+
+        let c = self.chars[pos];
+        if c == '∞' {
+            self.pos += 1;
+            return Some(Token { kind: TokenKind::Number, text: c.to_string(), pos });
+        }
+        if c.is_ascii_digit() || c == '.' {
+            let mut text = self.take_while(|l, i| l.chars[i].is_ascii_digit() || l.chars[i] == '.');
+            // scientific notation, e.g. "6.022e23"
+            if let Some((len, exponent)) = self.match_exponent(self.pos) {
+                text.push_str(&exponent);
+                self.pos += len;
+                return Some(Token { kind: TokenKind::Number, text, pos });
+            }
+            // a whole number followed by a fraction is one mixed number, e.g. "1 5/12"
+            if !text.contains('.') {
+                if let Some(len) = self.match_mixed_fraction(self.pos) {
+                    let fraction: String = self.chars[self.pos..self.pos + len].iter().collect();
+                    text.push(' ');
+                    text.push_str(fraction.trim_start());
+                    self.pos += len;
+                }
+            }
+            return Some(Token { kind: TokenKind::Number, text, pos });
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let text = self.take_while(|l, i| {
+                (l.chars[i].is_alphanumeric() || l.chars[i] == '_') && (i == pos || l.match_symbol(i).is_none())
+            });
+            // NaN is a number, as the results of the IEEE passthrough are displayed
+            let kind = if ExpressionBuilder::is_non_finite(&text) { TokenKind::Number } else { TokenKind::Identifier };
+            return Some(Token { kind, text, pos });
+        }
+
+        self.pos += 1;
+        Some(Token { kind: TokenKind::Unknown, text: c.to_string(), pos })
+    }
+}
+
+// error for a token which is not allowed where it appears, it would have been the next token of the builder
+fn unexpected(token: &str, builder: &ExpressionBuilder) -> CalcError {
+    CalcError::Syntax { token: token.to_string(), exp_idx: builder.next_exp_idx() }
+}
+
+// variable of an integral ∫(f, x, a, b) whose bracket is at the given token index,
+// with the index of the comma after the variable
+fn integral_variable(tokens: &[Token], bracket: usize, builder: &ExpressionBuilder) -> Result<(String, usize), CalcError> {
+    // the first comma outside of the brackets in the expression
+    let mut depth = 0;
+    let mut i = bracket + 1;
+    while i < tokens.len() && !(depth == 0 && tokens[i].text == EXP_UNIT_NAME_COMMA) {
+        match tokens[i].text.as_str() {
+            EXP_UNIT_NAME_OPEN_BRK => depth += 1,
+            EXP_UNIT_NAME_CLOSE_BRK if depth == 0 => break,
+            EXP_UNIT_NAME_CLOSE_BRK => depth -= 1,
+            _ => {},
+        }
+        i += 1;
+    }
+    // a comma, the name of the variable and another comma
+    let is_comma = |t: &Token| t.text == EXP_UNIT_NAME_COMMA;
+    let expected: [&dyn Fn(&Token) -> bool; 3] = [&is_comma, &|t| matches!(t.kind, TokenKind::Identifier), &is_comma];
+    for (k, valid) in expected.iter().enumerate() {
+        match tokens.get(i + k) {
+            Some(t) if valid(t) => {},
+            t => return Err(unexpected(t.map_or("", |t| t.text.as_str()), builder)),
+        }
+    }
+    Ok((tokens[i + 1].text.clone(), i + 2))
+}
+
+impl Expression {
+    pub fn parse_with_constants(input: &str, constants: &HashMap<String, String>) -> Result<Expression, CalcError> {
+        Self::parse_tokens(input, constants, &[])
+    }
+
+    /// parse the body of a user function, the parameter names become placeholders for the arguments
+    pub fn parse_function_body(input: &str, params: &[String], constants: &HashMap<String, String>) -> Result<Expression, CalcError> {
+        Self::parse_tokens(input, constants, params)
+    }
+
+    /// split a definition like "f(x, y) = x×y" into the function name, its parameters and its body
+    pub fn parse_function_header(input: &str) -> Option<(String, Vec<String>, String)> {
+        let (head, body) = input.split_once('=')?;
+        let tokens: Vec<Token> = Lexer::new(head).collect();
+        let is_name = |t: &Token| matches!(t.kind, TokenKind::Identifier);
+
+        if tokens.len() < 3 || !is_name(&tokens[0]) || tokens[1].text != EXP_UNIT_NAME_OPEN_BRK {
+            return None;
+        }
+        if tokens[tokens.len() - 1].text != EXP_UNIT_NAME_CLOSE_BRK {
+            return None;
+        }
+
+        // parameters separated by commas
+        let mut params = Vec::new();
+        let inner = &tokens[2..tokens.len() - 1];
+        for (i, token) in inner.iter().enumerate() {
+            if i % 2 == 0 && is_name(token) {
+                params.push(token.text.clone());
+            }
+            else if i % 2 == 1 && token.text == EXP_UNIT_NAME_COMMA {
+                continue;
+            }
+            else {
+                return None;
+            }
+        }
+        if params.is_empty() || inner.len().is_multiple_of(2) {
+            return None;
+        }
+        Some((tokens[0].text.clone(), params, body.to_string()))
+    }
+
+    /// split an assignment like "rate = 0.07" into the variable name and the expression
+    pub fn parse_assignment(input: &str) -> Option<(String, String)> {
+        let (name, value) = input.split_once('=')?;
+        let name = name.trim();
+        if !Lexer::is_identifier(name) {
+            return None;
+        }
+        Some((name.to_string(), value.to_string()))
+    }
+
+    fn parse_tokens(input: &str, constants: &HashMap<String, String>, params: &[String]) -> Result<Expression, CalcError> {
+        let tokens: Vec<Token> = Lexer::new(input).collect();
+        let mut builder = ExpressionBuilder::new_deferred();
+        // true when the next token should start an operand
+        let mut expect_operand = true;
+        // an operand right after another one is multiplied with it, except two numbers in a row
+        let mut after_number = false;
+        // positions of the brackets which are not closed yet
+        let mut open_brackets: Vec<i32> = Vec::new();
+        // the variables of the integrals are parameters up to the comma after them, the token index of which is kept
+        let mut params = params.to_vec();
+        let mut scopes: Vec<usize> = Vec::new();
+
+        let mut i = 0;
+        while i < tokens.len() {
+            if scopes.last() == Some(&i) {
+                scopes.pop();
+                params.pop();
+            }
+            let token = &tokens[i];
+            i += 1;
+            let follows_number = after_number;
+            after_number = matches!(token.kind, TokenKind::Number);
+
+            match token.kind {
+                TokenKind::Number => {
+                    if follows_number || !ExpressionBuilder::is_number(&token.text) {
+                        return Err(unexpected(&token.text, &builder));
+                    }
+                    builder.push_operand(token.text.clone());
+                    expect_operand = false;
+                    continue;
+                },
+                TokenKind::Identifier => {
+                    // the innermost variable of an integral hides the names outside of it
+                    if let Some(index) = params.iter().rposition(|p| *p == token.text) {
+                        builder.push_parameter(token.text.clone(), index);
+                        expect_operand = false;
+                        continue;
+                    }
+                    if Context::with_current(|c| c.borrow().variables.contains_key(&token.text)) {
+                        builder.push_variable(token.text.clone());
+                        expect_operand = false;
+                        continue;
+                    }
+                    if let Some(value) = constants.get(&token.text) {
+                        if !builder.push_constant(token.text.clone(), value) {
+                            return Err(unexpected(&token.text, &builder));
+                        }
+                        expect_operand = false;
+                        continue;
+                    }
+                    if !ExpressionBuilder::is_functor(&token.text) {
+                        let name = token.text.clone();
+                        let exp_idx = builder.next_exp_idx();
+                        let is_call = tokens.get(i).is_some_and(|next| next.text == EXP_UNIT_NAME_OPEN_BRK);
+                        return Err(if is_call { CalcError::UnknownFunction { name, exp_idx } } else { CalcError::UnknownVariable { name, exp_idx } });
+                    }
+                },
+                TokenKind::Unknown => {
+                    return Err(unexpected(&token.text, &builder));
+                },
+                TokenKind::Functor => {},
+            }
+
+            // functors, either symbols or named functions and operators
+            let name = token.text.as_str();
+            if name == EXP_UNIT_NAME_OPEN_BRK {
+                builder.push_functor(token.text.clone(), false)?;
+                // the bracket is the last token of the builder now
+                open_brackets.push(builder.next_exp_idx() - 1);
+                continue;
+            }
+            if name == EXP_UNIT_NAME_CLOSE_BRK {
+                if open_brackets.is_empty() {
+                    return Err(CalcError::MismatchedBrackets { exp_idx: builder.next_exp_idx() });
+                }
+                if expect_operand {
+                    return Err(unexpected(name, &builder));
+                }
+                open_brackets.pop();
+                builder.push_functor(token.text.clone(), false)?;
+                continue;
+            }
+            if name == EXP_UNIT_NAME_COMMA {
+                if open_brackets.is_empty() || expect_operand {
+                    return Err(unexpected(name, &builder));
+                }
+                builder.push_functor(token.text.clone(), false)?;
+                expect_operand = true;
+                continue;
+            }
+
+            // a sign in front of an operand, the builder turns a minus sign into a negation
+            if expect_operand && name == EXP_UNIT_NAME_ADD {
+                continue;
+            }
+            if expect_operand && name == EXP_UNIT_NAME_SUB {
+                builder.push_functor(token.text.clone(), false)?;
+                continue;
+            }
+
+            let valid = match ExpressionBuilder::functor_kind(name) {
+                Some(FunctorKind::Prefix) => expect_operand,
+                Some(FunctorKind::Postfix) => !expect_operand,
+                Some(FunctorKind::Infix) => {
+                    let valid = !expect_operand;
+                    expect_operand = true;
+                    valid
+                },
+                Some(FunctorKind::Function) => {
+                    if expect_operand && tokens.get(i).is_none_or(|next| next.text != EXP_UNIT_NAME_OPEN_BRK) {
+                        let next = tokens.get(i).map_or("", |next| next.text.as_str());
+                        return Err(CalcError::Syntax { token: next.to_string(), exp_idx: builder.next_exp_idx() + 1 });
+                    }
+                    expect_operand
+                },
+                None => false,
+            };
+            if !valid {
+                return Err(unexpected(name, &builder));
+            }
+            if name == EXP_UNIT_NAME_INTEGRAL {
+                let (variable, end) = integral_variable(&tokens, i, &builder)?;
+                params.push(variable);
+                scopes.push(end);
+            }
+            builder.push_functor(token.text.clone(), false)?;
+        }
+
+        if expect_operand {
+            return Err(unexpected("", &builder));
+        }
+        if let Some(exp_idx) = open_brackets.pop() {
+            return Err(CalcError::MismatchedBrackets { exp_idx });
+        }
+        builder.finish()
+    }
+}
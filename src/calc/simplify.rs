@@ -0,0 +1,297 @@
+use super::context::Context;
+use super::error::CalcError;
+use super::expression::*;
+use super::number::Number;
+
+/// an expression as a tree of values and functions, the form in which expressions are rewritten
+#[derive(Clone, Debug, PartialEq)]
+pub enum Term {
+    // a number, with the name of a named constant such as π or the literal it was written as
+    Constant { value: Number, name: Option<String>, literal: Option<String> },
+    Variable(String),
+    // parameter of a user function and its index
+    Parameter(String, usize),
+    // operator or function with its operands, named as in the function library
+    Apply(String, Vec<Term>),
+}
+
+impl Term {
+    pub fn number(value: Number) -> Term {
+        Term::Constant { value, name: None, literal: None }
+    }
+
+    pub fn apply(name: &str, args: Vec<Term>) -> Term {
+        Term::Apply(name.to_string(), args)
+    }
+
+    // value of a plain number, named constants are kept as they are written
+    fn as_number(&self) -> Option<&Number> {
+        match self {
+            Term::Constant { value, name: None, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    fn is_number(&self, v: &Number) -> bool {
+        self.as_number() == Some(v)
+    }
+
+    // a value which never fails, whatever the values of the variables are
+    fn cannot_fail(&self) -> bool {
+        !matches!(self, Term::Apply(..))
+    }
+
+    // a value which is known to be finite and not 0, so that its powers can cancel, e.g. π÷π is 1
+    fn is_nonzero_finite(&self) -> bool {
+        matches!(self, Term::Constant { value, .. } if value.is_finite() && !value.is_zero())
+    }
+}
+
+impl Expression {
+    /// an equivalent expression with the constant parts computed and the like terms collected,
+    /// e.g. 2x+3x+y×1+0 is 5x+y
+    pub fn simplify(&self) -> Result<Expression, CalcError> {
+        let term = self.to_term().ok_or(CalcError::EmptyExpression)?;
+        Expression::from_term(&simplify(term))
+    }
+}
+
+/// simplify a term from its leaves up
+pub fn simplify(term: Term) -> Term {
+    let (name, args) = match term {
+        Term::Apply(name, args) => (name, args.into_iter().map(simplify).collect::<Vec<Term>>()),
+        _ => return term,
+    };
+
+    // constant parts are computed, the user functions are kept as they may be redefined
+    let built_in = Context::with_current(|c| c.borrow().functions.is_built_in(&name));
+    if built_in && args.iter().all(|arg| matches!(arg, Term::Constant { .. })) {
+        let term = Term::Apply(name, args);
+        return match Expression::from_term(&term).and_then(|e| e.execute()) {
+            Ok(v) => Term::number(v),
+            // an error is left to the execution of the expression
+            Err(_) => term,
+        };
+    }
+
+    match name.as_str() {
+        EXP_UNIT_NAME_ADD | EXP_UNIT_NAME_SUB | EXP_UNIT_NAME_NEG => collect_sum(Term::Apply(name, args)),
+        EXP_UNIT_NAME_MUL | EXP_UNIT_NAME_DIV => simplify_product(Term::Apply(name, args)),
+        // x^1 = x and x^0 = 1, unless x is an error
+        EXP_UNIT_NAME_POW if args[1].is_number(&Number::one()) => args.into_iter().next().unwrap(),
+        EXP_UNIT_NAME_POW if args[1].is_number(&Number::zero()) && args[0].cannot_fail() => Term::number(Number::one()),
+        _ => Term::Apply(name, args),
+    }
+}
+
+fn simplify_product(term: Term) -> Term {
+    let mut product = Product::new();
+    product.add(term);
+    product.to_term(&Number::one())
+}
+
+// both numbers as exact fractions when one of them is a fraction, so that a coefficient divided by a number stays exact
+fn exact(a: &Number, b: &Number) -> (Number, Number) {
+    if a.is_fraction() || b.is_fraction() {
+        (a.to_fraction(), b.to_fraction())
+    }
+    else {
+        (a.clone(), b.clone())
+    }
+}
+
+// a factor which is finite whatever the values of the variables are, so that a product which is 0 can leave it out
+fn is_finite_factor(base: &Term, exponent: &Number) -> bool {
+    matches!(base, Term::Constant { value, .. } if value.is_finite() && (exponent.is_positive() || !value.is_zero()))
+}
+
+// factors of a product, with the plain numbers multiplied into a coefficient
+struct Product {
+    coefficient: Number,
+    // bases and their exponents, the powers of the same base are merged, e.g. x×x² is x³,
+    // a divisor is a negative exponent
+    factors: Vec<(Term, Number)>,
+}
+
+impl Product {
+    fn new() -> Self {
+        Self {
+            coefficient: Number::one(),
+            factors: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, term: Term) {
+        self.add_power(term, &Number::one());
+    }
+
+    // add a factor raised to a power of 1 or −1
+    fn add_power(&mut self, term: Term, power: &Number) {
+        if let Some(v) = term.as_number() {
+            // the numbers divided by are kept as fractions, so that x÷3 is not written with a rounded third
+            let factor = if *power == Number::one() {
+                Some(v.clone())
+            }
+            else if v.is_finite() && !v.is_zero() && !v.is_complex() {
+                Some(Number::one().to_fraction().div(&v.to_fraction(), Number::working_digits()))
+            }
+            else {
+                None
+            };
+            if let Some(factor) = factor {
+                let (a, b) = exact(&self.coefficient, &factor);
+                self.coefficient = a * b;
+                return;
+            }
+        }
+        let (base, exponent) = match term {
+            Term::Apply(name, args) if name == EXP_UNIT_NAME_MUL => {
+                for arg in args {
+                    self.add_power(arg, power);
+                }
+                return;
+            },
+            Term::Apply(name, mut args) if name == EXP_UNIT_NAME_NEG => {
+                self.coefficient = -&self.coefficient;
+                self.add_power(args.pop().unwrap(), power);
+                return;
+            },
+            // a division by 0 is kept as it is, it is an error
+            Term::Apply(name, mut args) if name == EXP_UNIT_NAME_DIV && !args[1].is_number(&Number::zero()) => {
+                let divisor = args.pop().unwrap();
+                self.add_power(args.pop().unwrap(), power);
+                self.add_power(divisor, &-power);
+                return;
+            },
+            Term::Apply(name, mut args) if name == EXP_UNIT_NAME_POW && args[1].as_number().is_some() => {
+                let exponent = args.pop().unwrap().as_number().unwrap().clone();
+                (args.pop().unwrap(), exponent)
+            },
+            Term::Apply(name, mut args) if name == EXP_UNIT_NAME_SQR => (args.pop().unwrap(), Number::from(2)),
+            term => (term, Number::one()),
+        };
+        let exponent = &exponent * power;
+        // the powers of a base which may be 0 or not finite do not cancel, e.g. x÷x stays as it is
+        let cancels = |e: &Number| e.is_negative() != exponent.is_negative();
+        match self.factors.iter_mut().find(|(b, e)| *b == base && (base.is_nonzero_finite() || !cancels(e))) {
+            Some((_, e)) => *e = &*e + &exponent,
+            None => self.factors.push((base, exponent)),
+        }
+    }
+
+    // every factor is finite, so the product is 0 when the coefficient is
+    fn is_finite(&self) -> bool {
+        self.factors.iter().all(|(base, e)| is_finite_factor(base, e))
+    }
+
+    // the factors without the coefficient, in an order which does not depend on how they were written
+    fn key(&self) -> String {
+        let mut factors: Vec<String> = self.factors.iter()
+            .filter(|(_, e)| !e.is_zero())
+            .map(|factor| format!("{:?}", factor))
+            .collect();
+        factors.sort();
+        factors.join("×")
+    }
+
+    // the product times a number, with the number first and the divisors last, e.g. 2x×y÷z,
+    // a fraction is written as its numerator and denominator, e.g. 2x÷3
+    fn to_term(&self, times: &Number) -> Term {
+        let coefficient = &self.coefficient * times;
+        let power = |base: &Term, e: Number| if e == Number::one() { base.clone() } else { Term::apply(EXP_UNIT_NAME_POW, vec![base.clone(), Term::number(e)]) };
+        let mut factors: Vec<Term> = self.factors.iter()
+            .filter(|(_, e)| e.is_positive())
+            .map(|(base, e)| power(base, e.clone()))
+            .collect();
+        let mut divisors: Vec<Term> = self.factors.iter()
+            .filter(|(_, e)| e.is_negative())
+            .map(|(base, e)| power(base, -e))
+            .collect();
+        if (coefficient.is_zero() && self.is_finite()) || (factors.is_empty() && divisors.is_empty()) {
+            return Term::number(coefficient.to_decimal(Number::working_digits()));
+        }
+
+        // a negative product is negated, e.g. −2x rather than -2×x
+        let negative = coefficient.is_negative();
+        let coefficient = if negative { -coefficient } else { coefficient };
+        let (coefficient, denominator) = coefficient.fraction_parts().unwrap_or((coefficient, Number::one()));
+        if coefficient != Number::one() {
+            factors.insert(0, Term::number(coefficient));
+        }
+        if denominator != Number::one() {
+            divisors.insert(0, Term::number(denominator));
+        }
+        let mut term = product(factors).unwrap_or_else(|| Term::number(Number::one()));
+        if negative {
+            term = Term::apply(EXP_UNIT_NAME_NEG, vec![term]);
+        }
+        if let Some(divisor) = product(divisors) {
+            term = Term::apply(EXP_UNIT_NAME_DIV, vec![term, divisor]);
+        }
+        term
+    }
+}
+
+// factors multiplied from the left, none for an empty list
+fn product(factors: Vec<Term>) -> Option<Term> {
+    factors.into_iter().reduce(|term, factor| Term::apply(EXP_UNIT_NAME_MUL, vec![term, factor]))
+}
+
+// terms of a sum, the like terms are added, e.g. 2x−y+3x is 5x−y
+fn collect_sum(term: Term) -> Term {
+    let mut terms: Vec<(String, Product)> = Vec::new();
+    let mut constant = Number::zero();
+    let mut pending = vec![(term, Number::one())];
+    while let Some((term, sign)) = pending.pop() {
+        if let Some(v) = term.as_number() {
+            constant = constant + &sign * v;
+            continue;
+        }
+        match term {
+            // the operands are popped in their order
+            Term::Apply(name, mut args) if name == EXP_UNIT_NAME_ADD || name == EXP_UNIT_NAME_SUB => {
+                let b = args.pop().unwrap();
+                let b_sign = if name == EXP_UNIT_NAME_SUB { -&sign } else { sign.clone() };
+                pending.push((b, b_sign));
+                pending.push((args.pop().unwrap(), sign));
+            },
+            Term::Apply(name, mut args) if name == EXP_UNIT_NAME_NEG => pending.push((args.pop().unwrap(), -sign)),
+            term => {
+                let mut product = Product::new();
+                product.add(term);
+                product.coefficient = product.coefficient * sign;
+                let key = product.key();
+                // like terms which may not be finite do not cancel, e.g. x−x stays as it is
+                let cancels = |p: &Product| {
+                    let (a, b) = exact(&p.coefficient, &product.coefficient);
+                    (a + b).is_zero() && !p.is_finite()
+                };
+                match terms.iter_mut().find(|(k, p)| *k == key && !cancels(p)) {
+                    Some((_, p)) => {
+                        let (a, b) = exact(&p.coefficient, &product.coefficient);
+                        p.coefficient = a + b;
+                    },
+                    None => terms.push((key, product)),
+                }
+            },
+        }
+    }
+
+    // the numbers come last, the negative terms are subtracted, e.g. x−2y+1
+    let mut sum: Option<Term> = None;
+    for (_, product) in terms.iter().filter(|(_, p)| !p.coefficient.is_zero() || !p.is_finite()) {
+        sum = Some(match sum {
+            None => product.to_term(&Number::one()),
+            Some(sum) if product.coefficient.is_negative() => {
+                Term::apply(EXP_UNIT_NAME_SUB, vec![sum, product.to_term(&-Number::one())])
+            },
+            Some(sum) => Term::apply(EXP_UNIT_NAME_ADD, vec![sum, product.to_term(&Number::one())]),
+        });
+    }
+    match sum {
+        None => Term::number(constant),
+        Some(sum) if constant.is_zero() => sum,
+        Some(sum) if constant.is_negative() => Term::apply(EXP_UNIT_NAME_SUB, vec![sum, Term::number(-constant)]),
+        Some(sum) => Term::apply(EXP_UNIT_NAME_ADD, vec![sum, Term::number(constant)]),
+    }
+}
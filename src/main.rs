@@ -4,30 +4,89 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use calc::Calculator;
+use calc::DisplayMode;
 use calc::Feature;
+use calc::NumberFormat;
+use calc::Separators;
+use calc::EXPONENT_KEY;
+use calc::FRACTION_SEPARATOR;
+use calc::VARIABLE_KEY;
 
+use calc::EXP_UNIT_NAME_ABS;
 use calc::EXP_UNIT_NAME_ADD;
+use calc::EXP_UNIT_NAME_AND;
+use calc::EXP_UNIT_NAME_COMBINATIONS;
+use calc::EXP_UNIT_NAME_COMMA;
 use calc::EXP_UNIT_NAME_DIV;
+use calc::EXP_UNIT_NAME_DOUBLE_FACTORIAL;
+use calc::EXP_UNIT_NAME_EXP;
+use calc::EXP_UNIT_NAME_EXP10;
+use calc::EXP_UNIT_NAME_FACTORIAL;
+use calc::EXP_UNIT_NAME_GAMMA;
+use calc::EXP_UNIT_NAME_INT_DIV;
 use calc::EXP_UNIT_NAME_INV;
+use calc::EXP_UNIT_NAME_LN;
+use calc::EXP_UNIT_NAME_LOG10;
+use calc::EXP_UNIT_NAME_LOG2;
+use calc::EXP_UNIT_NAME_LOGB;
+use calc::EXP_UNIT_NAME_MOD;
 use calc::EXP_UNIT_NAME_MUL;
+use calc::EXP_UNIT_NAME_NOT;
+use calc::EXP_UNIT_NAME_OR;
+use calc::EXP_UNIT_NAME_PERMUTATIONS;
+use calc::EXP_UNIT_NAME_POW;
+use calc::EXP_UNIT_NAME_REM;
+use calc::EXP_UNIT_NAME_ROL;
+use calc::EXP_UNIT_NAME_ROOT;
+use calc::EXP_UNIT_NAME_ROR;
+use calc::EXP_UNIT_NAME_SHL;
+use calc::EXP_UNIT_NAME_SHR;
 use calc::EXP_UNIT_NAME_SQR;
 use calc::EXP_UNIT_NAME_SQRT;
 use calc::EXP_UNIT_NAME_SUB;
+use calc::EXP_UNIT_NAME_XOR;
 use druid::{
     theme, AppLauncher, Color, Data, Lens, LocalizedString, RenderContext, Widget, WidgetExt,
     WindowDesc,
 };
 
-use druid::widget::{CrossAxisAlignment, Flex, Label, Painter};
+use druid::widget::{CrossAxisAlignment, Either, Flex, Label, Painter, SizedBox};
 
 #[derive(Clone, Data, Lens)]
 struct AppData {
     history: String,
     value: String,
+    angle_mode: String,
+    second: bool,
+    hyp: bool,
+    // the programmer keypad is shown instead of the scientific one
+    programmer: bool,
     caculator: Rc<RefCell<Calculator>>,
 }
 
 
+// angle mode, followed by the display format, the fraction, complex and programmer modes and the IEEE passthrough when they are on
+fn mode_label(caculator: &Calculator) -> String {
+    let mut label = caculator.angle_mode().to_string();
+    let display_mode = caculator.number_format().mode;
+    if display_mode != DisplayMode::Auto {
+        label.push_str(&format!(" {}", display_mode));
+    }
+    if caculator.fraction_mode() {
+        label.push_str(" FRAC");
+    }
+    if caculator.complex_mode() {
+        label.push_str(" CPLX");
+    }
+    if let Some(mode) = caculator.integer_mode() {
+        label.push_str(&format!(" {}", mode));
+    }
+    if caculator.ieee_passthrough() {
+        label.push_str(" IEEE");
+    }
+    label
+}
+
 impl AppData {
     fn on_exp_key(&mut self, key: String) {
         let mut caculator = self.caculator.borrow_mut();
@@ -45,10 +104,9 @@ impl AppData {
                 }
             },
             Err(s) => {
-                self.value = s;
+                // keep the input, so the user can correct it
+                self.value = s.to_string();
                 self.history = caculator.build_history();
-
-                let _ = caculator.reset();
             }
         };
     }
@@ -56,6 +114,8 @@ impl AppData {
     fn on_feature_key(&mut self, feature: &Feature) {
         let mut caculator = self.caculator.borrow_mut();
         let state = caculator.perform_feature(feature);
+        self.angle_mode = mode_label(&caculator);
+        self.programmer = caculator.integer_mode().is_some();
         match state {
             Ok(t) => {
                 match t {
@@ -69,10 +129,9 @@ impl AppData {
                 }
             },
             Err(s) => {
+                // keep the input, so the user can correct it
                 self.value = s.to_string();
                 self.history = caculator.build_history();
-
-                let _ = caculator.reset();
             }
         };
     }
@@ -127,6 +186,19 @@ fn op_feature(feature: Feature) -> impl Widget<AppData> {
         Feature::MR => "MR",
         Feature::Eval => "=",
         Feature::DEL => "⌫",
+        Feature::Negate => "±",
+        Feature::AngleMode => "DRG",
+        Feature::ComplexMode => "CPLX",
+        Feature::ComplexFormat => "r∠θ",
+        Feature::FractionDecimal => "F↔D",
+        Feature::FractionMode => "FRAC",
+        Feature::ProgrammerMode => "PROG",
+        Feature::Radix => "BASE",
+        Feature::WordSize => "WORD",
+        Feature::Signedness => "SIGN",
+        Feature::DisplayMode => "FMT",
+        Feature::StoreVariable => "→x",
+        Feature::Derivative => "d/dx",
     };
 
     let label_str = label.to_string();
@@ -139,6 +211,131 @@ fn op_feature(feature: Feature) -> impl Widget<AppData> {
         .on_click(move |_ctx, data: &mut AppData, _env| data.on_feature_key(&feature))
 }
 
+// sin, cos and tan buttons are switched to their inverse and hyperbolic variants by the 2nd and hyp keys
+fn trig_function_name(name: &str, data: &AppData) -> String {
+    let mut function = name.to_string();
+    if data.hyp {
+        function.push('h');
+    }
+    if data.second {
+        function.insert(0, 'a');
+    }
+    function
+}
+
+fn trig_button(name: &'static str) -> impl Widget<AppData> {
+    let painter = Painter::new(|ctx, _, env| {
+        let bounds = ctx.size().to_rect();
+
+        ctx.fill(bounds, &env.get(theme::PRIMARY_DARK));
+
+        if ctx.is_hot() {
+            ctx.stroke(bounds.inset(-0.5), &Color::WHITE, 1.0);
+        }
+
+        if ctx.is_active() {
+            ctx.fill(bounds, &env.get(theme::PRIMARY_LIGHT));
+        }
+    });
+
+    Label::new(move |data: &AppData, _env: &_| {
+        let mut label = name.to_string();
+        if data.hyp {
+            label.push('h');
+        }
+        if data.second {
+            label.push_str("⁻¹");
+        }
+        label
+    })
+        .with_text_size(24.)
+        .center()
+        .background(painter)
+        .expand()
+        .on_click(move |_ctx, data: &mut AppData, _env| data.on_exp_key(trig_function_name(name, data)))
+}
+
+// the x button enters the variable x, with the 2nd key it stores the displayed value in x
+fn variable_button() -> impl Widget<AppData> {
+    let painter = Painter::new(|ctx, _, env| {
+        let bounds = ctx.size().to_rect();
+
+        ctx.fill(bounds, &env.get(theme::PRIMARY_DARK));
+
+        if ctx.is_hot() {
+            ctx.stroke(bounds.inset(-0.5), &Color::WHITE, 1.0);
+        }
+
+        if ctx.is_active() {
+            ctx.fill(bounds, &env.get(theme::PRIMARY_LIGHT));
+        }
+    });
+
+    Label::new(|data: &AppData, _env: &_| if data.second { format!("→{}", VARIABLE_KEY) } else { VARIABLE_KEY.to_string() })
+        .with_text_size(24.)
+        .center()
+        .background(painter)
+        .expand()
+        .on_click(|_ctx, data: &mut AppData, _env| {
+            if data.second {
+                data.on_feature_key(&Feature::StoreVariable);
+            }
+            else {
+                data.on_exp_key(VARIABLE_KEY.to_string());
+            }
+        })
+}
+
+// the point button enters a decimal point, with the 2nd key the comma between the arguments of a function
+fn point_button() -> impl Widget<AppData> {
+    let painter = Painter::new(|ctx, _, env| {
+        let bounds = ctx.size().to_rect();
+
+        ctx.fill(bounds, &env.get(theme::PRIMARY_DARK));
+
+        if ctx.is_hot() {
+            ctx.stroke(bounds.inset(-0.5), &Color::WHITE, 1.0);
+        }
+
+        if ctx.is_active() {
+            ctx.fill(bounds, &env.get(theme::PRIMARY_LIGHT));
+        }
+    });
+
+    Label::new(|data: &AppData, _env: &_| if data.second { EXP_UNIT_NAME_COMMA.to_string() } else { ".".to_string() })
+        .with_text_size(24.)
+        .center()
+        .background(painter)
+        .expand()
+        .on_click(|_ctx, data: &mut AppData, _env| {
+            let key = if data.second { EXP_UNIT_NAME_COMMA } else { "." };
+            data.on_exp_key(key.to_string());
+        })
+}
+
+fn shift_button(label: &str) -> impl Widget<bool> {
+    let painter = Painter::new(|ctx, on: &bool, env| {
+        let bounds = ctx.size().to_rect();
+
+        ctx.fill(bounds, &env.get(theme::PRIMARY_DARK));
+
+        if *on {
+            ctx.fill(bounds, &env.get(theme::PRIMARY_LIGHT));
+        }
+
+        if ctx.is_hot() {
+            ctx.stroke(bounds.inset(-0.5), &Color::WHITE, 1.0);
+        }
+    });
+
+    Label::new(label.to_string())
+        .with_text_size(24.)
+        .center()
+        .background(painter)
+        .expand()
+        .on_click(|_ctx, on: &mut bool, _env| *on = !*on)
+}
+
 fn digit_button(digit: char) -> impl Widget<AppData> {
     let painter = Painter::new(|ctx, _, env| {
         let bounds = ctx.size().to_rect();
@@ -162,6 +359,10 @@ fn digit_button(digit: char) -> impl Widget<AppData> {
         .on_click(move |_ctx, data: &mut AppData, _env| data.on_exp_key(digit.to_string()))
 }
 
+fn blank_button() -> impl Widget<AppData> {
+    SizedBox::empty().expand()
+}
+
 fn flex_row<T: Data>(
     w1: impl Widget<T> + 'static,
     w2: impl Widget<T> + 'static,
@@ -181,29 +382,70 @@ fn flex_row<T: Data>(
         .with_flex_child(w5, 1.0)
 }
 
-fn build_calc() -> impl Widget<AppData> {
-    let lb_history = Label::new(|data: &String, _env: &_| data.clone())
-        .with_text_size(14.0)
-        .lens(AppData::history)
-        .padding(5.0);
-    let lb_result = Label::new(|data: &String, _env: &_| data.clone())
-        .with_text_size(20.0)
-        .lens(AppData::value)
-        .padding(5.0);
+fn scientific_keypad() -> impl Widget<AppData> {
     Flex::column()
-        .with_flex_spacer(0.2)
-        .with_child(lb_history)
-        .with_flex_spacer(0.2)
-        .with_child(lb_result)
-        .with_flex_spacer(0.2)
-        .cross_axis_alignment(CrossAxisAlignment::End)
         .with_flex_child(
             flex_row(
-                op_feature(Feature::CE),
-                op_feature(Feature::C),
-                op_feature(Feature::MS),
-                op_feature(Feature::MR),
-                op_feature(Feature::DEL),
+                op_feature(Feature::ProgrammerMode),
+                op_button_label(EXPONENT_KEY.to_string()),
+                op_feature(Feature::DisplayMode),
+                variable_button(),
+                op_feature(Feature::Derivative),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                op_feature(Feature::FractionDecimal),
+                op_feature(Feature::FractionMode),
+                op_button_label("i".to_string()),
+                op_feature(Feature::ComplexMode),
+                op_feature(Feature::ComplexFormat),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                op_button_label_id("⅟x".to_string(), EXP_UNIT_NAME_INV.to_string()),
+                op_button_label_id("x²".to_string(), EXP_UNIT_NAME_SQR.to_string()),
+                op_button_label_id("xʸ".to_string(), EXP_UNIT_NAME_POW.to_string()),
+                op_button_label(EXP_UNIT_NAME_SQRT.to_string()),
+                op_button_label_id("ʸ√x".to_string(), EXP_UNIT_NAME_ROOT.to_string()),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                op_button_label(EXP_UNIT_NAME_LN.to_string()),
+                op_button_label_id("log₁₀".to_string(), EXP_UNIT_NAME_LOG10.to_string()),
+                op_button_label_id("log₂".to_string(), EXP_UNIT_NAME_LOG2.to_string()),
+                op_button_label_id("logᵧx".to_string(), EXP_UNIT_NAME_LOGB.to_string()),
+                op_button_label_id("|x|".to_string(), EXP_UNIT_NAME_ABS.to_string()),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                op_button_label_id("eˣ".to_string(), EXP_UNIT_NAME_EXP.to_string()),
+                op_button_label(EXP_UNIT_NAME_EXP10.to_string()),
+                op_feature(Feature::AngleMode),
+                shift_button("2nd").lens(AppData::second),
+                shift_button("hyp").lens(AppData::hyp),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                op_button_label_id("n!".to_string(), EXP_UNIT_NAME_FACTORIAL.to_string()),
+                op_button_label_id("n!!".to_string(), EXP_UNIT_NAME_DOUBLE_FACTORIAL.to_string()),
+                op_button_label_id("Γ(x)".to_string(), EXP_UNIT_NAME_GAMMA.to_string()),
+                op_button_label(EXP_UNIT_NAME_PERMUTATIONS.to_string()),
+                op_button_label(EXP_UNIT_NAME_COMBINATIONS.to_string()),
             ),
             1.0,
         )
@@ -212,8 +454,8 @@ fn build_calc() -> impl Widget<AppData> {
             flex_row(
                 op_button_label("(".to_string()),
                 op_button_label(")".to_string()),
-                op_button_label_id("⅟x".to_string(), EXP_UNIT_NAME_INV.to_string()),
                 op_button_label( "π".to_string()),
+                op_button_label(EXP_UNIT_NAME_MOD.to_string()),
                 op_button_label(EXP_UNIT_NAME_DIV.to_string()), //
             ),
             1.0,
@@ -224,7 +466,7 @@ fn build_calc() -> impl Widget<AppData> {
                 digit_button('7'),
                 digit_button('8'),
                 digit_button('9'),
-                op_button_label("tan".to_string()),
+                trig_button("tan"),
                 op_button_label(EXP_UNIT_NAME_MUL.to_string()),
             ),
             1.0,
@@ -235,7 +477,7 @@ fn build_calc() -> impl Widget<AppData> {
                 digit_button('4'),
                 digit_button('5'),
                 digit_button('6'),
-                op_button_label("cos".to_string()),
+                trig_button("cos"),
                 op_button_label(EXP_UNIT_NAME_SUB.to_string()),
             ),
             1.0,
@@ -246,7 +488,7 @@ fn build_calc() -> impl Widget<AppData> {
                 digit_button('1'),
                 digit_button('2'),
                 digit_button('3'),
-                op_button_label("sin".to_string()),
+                trig_button("sin"),
                 op_button_label(EXP_UNIT_NAME_ADD.to_string()),
             ),
             1.0,
@@ -254,31 +496,183 @@ fn build_calc() -> impl Widget<AppData> {
         .with_spacer(1.0)
         .with_flex_child(
             flex_row(
+                op_feature(Feature::Negate),
                 digit_button('0'),
-                op_button_label(".".to_string()),
-                op_button_label(EXP_UNIT_NAME_SQRT.to_string()),
-                op_button_label_id("x²".to_string(), EXP_UNIT_NAME_SQR.to_string()),
+                point_button(),
+                op_button_label_id("a b/c".to_string(), FRACTION_SEPARATOR.to_string()),
                 op_feature(Feature::Eval),
             ),
             1.0,
         )
 }
 
+// integer keypad of the programmer mode, with the hexadecimal digits and the bitwise operators
+fn programmer_keypad() -> impl Widget<AppData> {
+    Flex::column()
+        .with_flex_child(
+            flex_row(
+                op_feature(Feature::ProgrammerMode),
+                op_feature(Feature::Radix),
+                op_feature(Feature::WordSize),
+                op_feature(Feature::Signedness),
+                blank_button(),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                digit_button('A'),
+                digit_button('B'),
+                digit_button('C'),
+                op_button_label(EXP_UNIT_NAME_AND.to_string()),
+                op_button_label(EXP_UNIT_NAME_OR.to_string()),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                digit_button('D'),
+                digit_button('E'),
+                digit_button('F'),
+                op_button_label(EXP_UNIT_NAME_XOR.to_string()),
+                op_button_label(EXP_UNIT_NAME_NOT.to_string()),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                op_button_label(EXP_UNIT_NAME_SHL.to_string()),
+                op_button_label(EXP_UNIT_NAME_SHR.to_string()),
+                op_button_label(EXP_UNIT_NAME_ROL.to_string()),
+                op_button_label(EXP_UNIT_NAME_ROR.to_string()),
+                blank_button(),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                op_button_label("(".to_string()),
+                op_button_label(")".to_string()),
+                op_button_label(EXP_UNIT_NAME_MOD.to_string()),
+                op_button_label(EXP_UNIT_NAME_REM.to_string()),
+                op_button_label(EXP_UNIT_NAME_DIV.to_string()),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                digit_button('7'),
+                digit_button('8'),
+                digit_button('9'),
+                op_button_label(EXP_UNIT_NAME_INT_DIV.to_string()),
+                op_button_label(EXP_UNIT_NAME_MUL.to_string()),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                digit_button('4'),
+                digit_button('5'),
+                digit_button('6'),
+                blank_button(),
+                op_button_label(EXP_UNIT_NAME_SUB.to_string()),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                digit_button('1'),
+                digit_button('2'),
+                digit_button('3'),
+                blank_button(),
+                op_button_label(EXP_UNIT_NAME_ADD.to_string()),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        .with_flex_child(
+            flex_row(
+                op_feature(Feature::Negate),
+                digit_button('0'),
+                blank_button(),
+                blank_button(),
+                op_feature(Feature::Eval),
+            ),
+            1.0,
+        )
+}
+
+fn build_calc() -> impl Widget<AppData> {
+    let lb_history = Label::new(|data: &String, _env: &_| data.clone())
+        .with_text_size(14.0)
+        .lens(AppData::history)
+        .padding(5.0);
+    let lb_result = Label::new(|data: &String, _env: &_| data.clone())
+        .with_text_size(20.0)
+        .lens(AppData::value)
+        .padding(5.0);
+    let lb_angle_mode = Label::new(|data: &String, _env: &_| data.clone())
+        .with_text_size(12.0)
+        .lens(AppData::angle_mode)
+        .padding(5.0);
+    let keypad = Either::new(|data: &AppData, _env| data.programmer, programmer_keypad(), scientific_keypad());
+    Flex::column()
+        .with_flex_spacer(0.2)
+        .with_child(
+            Flex::row()
+                .with_child(lb_angle_mode)
+                .with_flex_spacer(1.0)
+                .with_child(lb_history),
+        )
+        .with_flex_spacer(0.2)
+        .with_child(lb_result)
+        .with_flex_spacer(0.2)
+        .cross_axis_alignment(CrossAxisAlignment::End)
+        .with_flex_child(
+            flex_row(
+                op_feature(Feature::CE),
+                op_feature(Feature::C),
+                op_feature(Feature::MS),
+                op_feature(Feature::MR),
+                op_feature(Feature::DEL),
+            ),
+            1.0,
+        )
+        .with_spacer(1.0)
+        // the rows of the keypad are as high as the row above
+        .with_flex_child(keypad, 11.0)
+}
+
 pub fn main() {
     let window = WindowDesc::new(build_calc())
-        .window_size((403., 400.))
+        .window_size((403., 720.))
         .resizable(false)
         .title(
             LocalizedString::new("calc-demo-window-title").with_placeholder("Simple Calculator"),
         );
 
+    let mut caculator = Calculator::new();
+    // thousands are grouped with the separators of the user's locale
+    caculator.set_number_format(NumberFormat { grouping: true, separators: Separators::from_env(), ..NumberFormat::default() });
     let app_data: AppData = AppData {
         history: String::new(),
         value: "0".to_string(),
-        caculator: Rc::new(RefCell::new(Calculator::new()))
+        angle_mode: mode_label(&caculator),
+        second: false,
+        hyp: false,
+        programmer: false,
+        caculator: Rc::new(RefCell::new(caculator))
     };
 
-    app_data.caculator.borrow_mut().add_constant("π".to_string(), "3.14159265358979323846".to_string());
+    app_data.caculator.borrow_mut().add_constant("i".to_string(), "i".to_string());
+    app_data.caculator.borrow_mut().add_constant("π".to_string(), "3.1415926535897932384626433832795028841971693993751058209749445923078164062862089986280348253421170679".to_string());
 
     AppLauncher::with_window(window)
         .log_to_console()